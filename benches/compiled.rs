@@ -0,0 +1,75 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use datalink::{id::ID, value::ValueBuiler, Data};
+use filters::{DataFilter, Filter, Range, TextFilter};
+
+struct Item {
+    id: u128,
+    text: String,
+    number: i64,
+}
+
+impl Data for Item {
+    fn provide_value<'d>(&'d self, value: &mut dyn ValueBuiler<'d>) {
+        value.str(self.text.as_str().into());
+        value.i64(self.number);
+    }
+
+    fn get_id(&self) -> Option<ID> {
+        Some(ID::from(self.id))
+    }
+}
+
+/// Alternating `And`s and `Or`s nested `depth` levels deep, with a mix of
+/// leaves that match some of the items.
+fn deep(depth: u32, seed: &mut i128) -> DataFilter {
+    *seed += 1;
+    if depth == 0 {
+        return match *seed % 4 {
+            0 => DataFilter::text(TextFilter::contains(format!("{}", *seed % 10))),
+            1 => DataFilter::int(Range::ge(*seed % 50)),
+            2 => DataFilter::id(ID::from(u128::try_from(*seed % 20).unwrap_or_default())),
+            _ => !DataFilter::text(TextFilter::starts_with("item")),
+        };
+    }
+    let operands = (0..3).map(|_| deep(depth - 1, seed)).collect::<Vec<_>>();
+    let f = match depth % 2 {
+        0 => DataFilter::And(operands.into()),
+        _ => DataFilter::Or(operands.into()),
+    };
+    if *seed % 3 == 0 {
+        !f
+    } else {
+        f
+    }
+}
+
+fn items() -> Vec<Item> {
+    (0..100u32)
+        .map(|i| Item {
+            id: u128::from(i % 20),
+            text: format!("item {i}"),
+            number: i64::from(i % 50),
+        })
+        .collect()
+}
+
+fn bench(c: &mut Criterion) {
+    let items = items();
+    let mut group = c.benchmark_group("deep");
+    for depth in [2, 4, 6, 8] {
+        let tree = deep(depth, &mut 0);
+        let compiled = tree.compile();
+        group.bench_with_input(BenchmarkId::new("tree", depth), &tree, |b, f| {
+            b.iter(|| items.iter().filter(|i| f.matches(black_box(*i))).count());
+        });
+        group.bench_with_input(BenchmarkId::new("compiled", depth), &compiled, |b, f| {
+            b.iter(|| items.iter().filter(|i| f.matches(black_box(*i))).count());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
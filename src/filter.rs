@@ -4,13 +4,79 @@ mod and;
 pub use and::And;
 mod not;
 pub use not::Not;
+mod xor;
+pub use xor::Xor;
+mod threshold;
+pub use threshold::{AtLeast, AtMost, Exactly};
+mod implies;
+pub use implies::Implies;
+mod iff;
+pub use iff::Iff;
+mod and_then;
+pub use and_then::AndThen;
+mod or_else;
+pub use or_else::OrElse;
+mod xor_with;
+pub use xor_with::XorWith;
+mod all_of;
+pub use all_of::AllOf;
+mod any_of;
+pub use any_of::AnyOf;
 mod text;
+#[cfg(feature = "regex")]
+pub use text::RegexError;
 pub use text::TextFilter;
+mod textset;
+pub use textset::TextSet;
+mod range;
+pub use range::Range;
+mod from_fn;
+pub use from_fn::{from_fn, FnFilter};
+mod ext;
+pub use ext::FilterExt;
+mod truth;
+pub use truth::{TriFilter, Truth};
+mod explain;
+pub use explain::{Explain, Explanation, Outcome};
+mod display;
+pub use display::{Infix, Precedence};
+mod cost;
+pub use cost::Cost;
+mod adaptive;
+pub use adaptive::Adaptive;
+mod dyn_filter;
+pub use dyn_filter::{optimize_boxed, BoxedFilter, DynFilter};
+mod iter;
+pub use iter::{ByRef, ByValue, FilterBy, IteratorFilterExt, MatchesItem};
+#[cfg(feature = "datalink")]
+mod data;
+#[cfg(feature = "datalink")]
+pub use data::DataFilter;
+#[cfg(feature = "datalink")]
+mod link;
+#[cfg(feature = "datalink")]
+pub use link::LinkFilter;
+#[cfg(feature = "datalink")]
+mod compiled;
+#[cfg(feature = "datalink")]
+pub use compiled::CompiledFilter;
+#[cfg(feature = "datalink")]
+mod normalize;
+#[cfg(feature = "datalink")]
+pub use normalize::{Normalize, TooLarge};
+#[cfg(feature = "datalink")]
+mod parse;
+#[cfg(feature = "datalink")]
+pub use parse::{parse, ParseError, ParseErrorKind};
 #[cfg(test)]
 mod testfilter;
 #[cfg(test)]
 pub use testfilter::TestFilter;
 
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
 pub trait Filter<T: ?Sized> {
     fn matches(&self, obj: &T) -> bool;
 
@@ -27,6 +93,17 @@ pub trait Optimizable {
     #[inline]
     fn optimize(&mut self) {}
 
+    /// Optimizes the filter and then reorders it by [`Cost`], so cheap and
+    /// decisive operands run first.
+    #[inline]
+    fn optimize_by_cost(&mut self)
+    where
+        Self: Cost + Sized,
+    {
+        self.optimize();
+        self.reorder();
+    }
+
     #[inline]
     fn as_bool(&self) -> Option<bool> {
         None
@@ -41,7 +118,7 @@ pub trait FalsyDefault {
     fn falsy_default() -> Self;
 }
 
-impl<T> Filter<T> for bool {
+impl<T: ?Sized> Filter<T> for bool {
     #[inline]
     fn matches(&self, _: &T) -> bool {
         *self
@@ -69,7 +146,7 @@ impl FalsyDefault for bool {
     }
 }
 
-impl<F: Filter<T>, T: ?Sized> Filter<T> for Box<F> {
+impl<F: Filter<T> + ?Sized, T: ?Sized> Filter<T> for Box<F> {
     #[inline]
     fn matches(&self, obj: &T) -> bool {
         self.as_ref().matches(obj)
@@ -81,6 +158,11 @@ impl<F: Optimizable> Optimizable for Box<F> {
     fn optimize(&mut self) {
         self.as_mut().optimize();
     }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
 }
 
 impl<F: FalsyDefault> FalsyDefault for Box<F> {
@@ -97,6 +179,170 @@ impl<F: TruthyDefault> TruthyDefault for Box<F> {
     }
 }
 
+impl<F: Filter<T> + ?Sized, T: ?Sized> Filter<T> for &F {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        (**self).matches(obj)
+    }
+}
+
+impl<F: Optimizable + ?Sized> Optimizable for &F {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        (**self).as_bool()
+    }
+}
+
+impl<F: Filter<T> + ?Sized, T: ?Sized> Filter<T> for Rc<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.as_ref().matches(obj)
+    }
+}
+
+impl<F: Optimizable + ?Sized> Optimizable for Rc<F> {
+    /// Only optimizes the filter if it isn't shared.
+    #[inline]
+    fn optimize(&mut self) {
+        if let Some(f) = Rc::get_mut(self) {
+            f.optimize();
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
+}
+
+impl<F: FalsyDefault> FalsyDefault for Rc<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Rc::new(F::falsy_default())
+    }
+}
+
+impl<F: TruthyDefault> TruthyDefault for Rc<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Rc::new(F::truthy_default())
+    }
+}
+
+impl<F: Filter<T> + ?Sized, T: ?Sized> Filter<T> for Arc<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.as_ref().matches(obj)
+    }
+}
+
+impl<F: Optimizable + ?Sized> Optimizable for Arc<F> {
+    /// Only optimizes the filter if it isn't shared.
+    #[inline]
+    fn optimize(&mut self) {
+        if let Some(f) = Arc::get_mut(self) {
+            f.optimize();
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
+}
+
+impl<F: FalsyDefault> FalsyDefault for Arc<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Arc::new(F::falsy_default())
+    }
+}
+
+impl<F: TruthyDefault> TruthyDefault for Arc<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Arc::new(F::truthy_default())
+    }
+}
+
+impl<F: Filter<T> + ToOwned + ?Sized, T: ?Sized> Filter<T> for Cow<'_, F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.as_ref().matches(obj)
+    }
+}
+
+impl<F: Optimizable + Clone> Optimizable for Cow<'_, F> {
+    /// Only optimizes owned filters, to avoid cloning borrowed ones.
+    #[inline]
+    fn optimize(&mut self) {
+        if let Cow::Owned(f) = self {
+            f.optimize();
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
+}
+
+impl<F: FalsyDefault + Clone> FalsyDefault for Cow<'_, F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Cow::Owned(F::falsy_default())
+    }
+}
+
+impl<F: TruthyDefault + Clone> TruthyDefault for Cow<'_, F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Cow::Owned(F::truthy_default())
+    }
+}
+
+/// `None` doesn't constrain anything and matches every object.
+impl<F: Filter<T>, T: ?Sized> Filter<T> for Option<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.as_ref().is_none_or(|f| f.matches(obj))
+    }
+}
+
+impl<F: Optimizable> Optimizable for Option<F> {
+    #[inline]
+    fn optimize(&mut self) {
+        if let Some(f) = self {
+            f.optimize();
+            if f.as_bool() == Some(true) {
+                *self = None;
+            }
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Some(f) => f.as_bool(),
+            None => Some(true),
+        }
+    }
+}
+
+impl<F: FalsyDefault> FalsyDefault for Option<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Some(F::falsy_default())
+    }
+}
+
+impl<F> TruthyDefault for Option<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +351,56 @@ mod tests {
     fn object_safety() {
         fn _f<On>(_d: &dyn Filter<On>) {}
     }
+
+    #[test]
+    fn boxed_constant() {
+        let mut f = And::from(vec![Box::new(TestFilter::Any), Box::new(TestFilter::None)]);
+
+        assert_eq!(f.as_bool(), Some(false));
+        f.optimize();
+        assert_eq!(f.as_bool(), Some(false));
+        assert_eq!(*f[0], TestFilter::None);
+    }
+
+    #[test]
+    fn shared() {
+        let text = TextFilter::from("foo");
+        let f = Or::from(vec![&text, &text]);
+        assert!(f.matches("foo"));
+
+        let f = Arc::new(TextFilter::from("foo"));
+        let handle = std::thread::spawn({
+            let f = Arc::clone(&f);
+            move || f.matches("foo")
+        });
+        assert!(handle.join().unwrap());
+
+        let mut f = Rc::new(Not(TestFilter::None));
+        let shared = Rc::clone(&f);
+        f.optimize();
+        assert_eq!(shared.as_bool(), Some(true));
+        assert!(f.matches(&()));
+
+        let mut f: Cow<'_, bool> = Cow::Borrowed(&true);
+        f.optimize();
+        assert!(matches!(f, Cow::Borrowed(_)));
+        assert!(f.matches("foo"));
+        assert_eq!(Cow::<bool>::falsy_default().as_bool(), Some(false));
+    }
+
+    #[test]
+    fn option() {
+        let mut f = Some(TestFilter::Any);
+        f.optimize();
+        assert_eq!(f, None);
+        assert!(f.matches(&()));
+
+        let f = Option::<TestFilter>::falsy_default();
+        assert_eq!(f.as_bool(), Some(false));
+        assert!(!f.matches(&()));
+
+        let f = And::from(vec![None, Some(TextFilter::from("foo"))]);
+        assert!(f.matches("foo"));
+        assert!(!f.matches("bar"));
+    }
 }
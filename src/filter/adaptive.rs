@@ -0,0 +1,273 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use super::{And, Filter, Optimizable, Or};
+
+/// Wraps an [`And`] or [`Or`], learning which operands short circuit most
+/// often and running those first.
+///
+/// Every `period` evaluations the operands are reordered by the observed
+/// rate at which they decided the outcome. Older observations are halved on
+/// each reorder, so the order follows drifting inputs. Evaluation only needs
+/// `&self`, so the filter can be shared between threads.
+#[derive(Debug)]
+pub struct Adaptive<C> {
+    inner: C,
+    order: RwLock<Box<[usize]>>,
+    stats: Box<[Stats]>,
+    evals: AtomicU64,
+    period: u64,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    evaluated: AtomicU64,
+    decided: AtomicU64,
+}
+
+impl Stats {
+    /// The fraction of evaluations that decided the outcome.
+    fn rate(&self) -> f64 {
+        let evaluated = self.evaluated.load(Ordering::Relaxed);
+        let decided = self.decided.load(Ordering::Relaxed);
+        if evaluated == 0 {
+            // unknown operands get a chance to prove themselves
+            return 0.5;
+        }
+        decided as f64 / evaluated as f64
+    }
+
+    fn decay(&self) {
+        for counter in [&self.evaluated, &self.decided] {
+            // a concurrent increment may get lost, which is fine for an estimate
+            counter.store(counter.load(Ordering::Relaxed) / 2, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<C> Adaptive<C> {
+    /// The default number of evaluations between reorders.
+    pub const DEFAULT_PERIOD: u64 = 1024;
+
+    #[inline]
+    #[must_use]
+    pub fn new<F>(inner: C) -> Self
+    where
+        C: Deref<Target = Vec<F>>,
+    {
+        let len = inner.len();
+        Self {
+            inner,
+            order: RwLock::new((0..len).collect()),
+            stats: (0..len).map(|_| Stats::default()).collect(),
+            evals: AtomicU64::new(0),
+            period: Self::DEFAULT_PERIOD,
+        }
+    }
+
+    /// Returns the wrapped filter with its operands in the learned order.
+    #[must_use]
+    pub fn into_inner<F>(self) -> C
+    where
+        C: DerefMut<Target = Vec<F>>,
+    {
+        let order = self.order.into_inner().unwrap_or_else(|e| e.into_inner());
+        let mut inner = self.inner;
+        let mut operands: Vec<Option<F>> =
+            std::mem::take(&mut *inner).into_iter().map(Some).collect();
+        *inner = order.iter().filter_map(|&i| operands[i].take()).collect();
+        inner
+    }
+
+    /// Sets the number of evaluations between reorders.
+    #[inline]
+    #[must_use]
+    pub fn with_period(mut self, period: u64) -> Self {
+        self.period = period.max(1);
+        self
+    }
+
+    /// The current evaluation order, as indices into the operands.
+    #[must_use]
+    pub fn order(&self) -> Vec<usize> {
+        self.read_order().to_vec()
+    }
+
+    #[inline]
+    fn read_order(&self) -> std::sync::RwLockReadGuard<'_, Box<[usize]>> {
+        // the order is always a valid permutation, even after a panic
+        self.order.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Evaluates `operands` in the learned order until one returns
+    /// `decisive`.
+    fn run<F: Filter<T>, T: ?Sized>(&self, operands: &[F], obj: &T, decisive: bool) -> bool {
+        let result = {
+            let order = self.read_order();
+            order.iter().any(|&i| {
+                let stats = &self.stats[i];
+                stats.evaluated.fetch_add(1, Ordering::Relaxed);
+                let decided = operands[i].matches(obj) == decisive;
+                if decided {
+                    stats.decided.fetch_add(1, Ordering::Relaxed);
+                }
+                decided
+            })
+        };
+        let evals = self.evals.fetch_add(1, Ordering::Relaxed) + 1;
+        // `is_multiple_of` needs Rust 1.87
+        #[allow(clippy::manual_is_multiple_of)]
+        if evals % self.period == 0 {
+            self.reorder();
+        }
+        if result {
+            decisive
+        } else {
+            !decisive
+        }
+    }
+
+    /// Sorts the operands by how often they decided the outcome, skipping
+    /// the reorder if another thread is already at it.
+    fn reorder(&self) {
+        let Ok(mut order) = self.order.try_write() else {
+            return;
+        };
+        let rates: Vec<f64> = self.stats.iter().map(Stats::rate).collect();
+        order.sort_by(|&a, &b| rates[b].total_cmp(&rates[a]));
+        for stats in self.stats.iter() {
+            stats.decay();
+        }
+    }
+
+    /// Forgets all observations, e.g. after the operands changed.
+    fn reset(&mut self, len: usize) {
+        *self.order.get_mut().unwrap_or_else(|e| e.into_inner()) = (0..len).collect();
+        self.stats = (0..len).map(|_| Stats::default()).collect();
+        *self.evals.get_mut() = 0;
+    }
+}
+
+macro_rules! adaptive {
+    ($ty:ident, $decisive:literal) => {
+        impl<F> From<$ty<F>> for Adaptive<$ty<F>> {
+            #[inline]
+            fn from(value: $ty<F>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<F: Filter<T>, T: ?Sized> Filter<T> for Adaptive<$ty<F>> {
+            #[inline]
+            fn matches(&self, obj: &T) -> bool {
+                self.run(&self.inner, obj, $decisive)
+            }
+        }
+
+        /// Optimizing may remove operands, so it resets the observations.
+        impl<F> Optimizable for Adaptive<$ty<F>>
+        where
+            $ty<F>: Optimizable,
+        {
+            #[inline]
+            fn optimize(&mut self) {
+                self.inner.optimize();
+                self.reset(self.inner.len());
+            }
+
+            #[inline]
+            fn as_bool(&self) -> Option<bool> {
+                self.inner.as_bool()
+            }
+        }
+    };
+}
+
+adaptive!(And, false);
+adaptive!(Or, true);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::{from_fn, Range, TestFilter};
+
+    #[test]
+    fn learns_order() {
+        let evals = AtomicUsize::new(0);
+        let counting = |bound: i32| {
+            let evals = &evals;
+            from_fn(move |n: &i32| {
+                evals.fetch_add(1, Ordering::Relaxed);
+                *n < bound
+            })
+        };
+        // the last operand rejects most values
+        let f = Adaptive::new(And::from(vec![counting(1000), counting(900), counting(10)]))
+            .with_period(16);
+
+        for n in 0..100 {
+            assert_eq!(f.matches(&n), n < 10);
+        }
+        assert_eq!(f.order()[0], 2);
+
+        evals.store(0, Ordering::Relaxed);
+        for n in 100..200 {
+            assert!(!f.matches(&n));
+        }
+        assert_eq!(evals.load(Ordering::Relaxed), 100);
+    }
+
+    #[test]
+    fn follows_drift() {
+        let f = Adaptive::new(Or::from(vec![Range::lt(0), Range::ge(0)])).with_period(8);
+
+        for n in 0..32 {
+            assert!(f.matches(&n));
+        }
+        assert_eq!(f.order(), [1, 0]);
+        for n in -64..0 {
+            assert!(f.matches(&n));
+        }
+        assert_eq!(f.order(), [0, 1]);
+
+        let or = f.into_inner();
+        assert_eq!(or[0], Range::lt(0));
+    }
+
+    #[test]
+    fn threads() {
+        let f = Adaptive::new(And::from(vec![
+            Range::ge(0),
+            Range::lt(50),
+            Range::lt(1000),
+        ]))
+        .with_period(4);
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let f = &f;
+                s.spawn(move || {
+                    for n in t * 100..(t + 1) * 100 {
+                        assert_eq!(f.matches(&n), n < 50);
+                    }
+                });
+            }
+        });
+        let mut order = f.order();
+        order.sort_unstable();
+        assert_eq!(order, [0, 1, 2]);
+    }
+
+    #[test]
+    fn optimize() {
+        let mut f = Adaptive::new(And::from(vec![TestFilter::Any, TestFilter::Panic]));
+        assert_eq!(f.as_bool(), None);
+
+        f.inner[1] = TestFilter::Any;
+        f.optimize();
+        assert_eq!(f.order(), Vec::<usize>::new());
+        assert!(f.matches(&()));
+    }
+}
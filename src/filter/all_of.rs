@@ -0,0 +1,183 @@
+use super::cost;
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if all filters of a tuple match.
+///
+/// Unlike [`And`](super::And), the filters can have different types and
+/// don't need to be allocated.
+///
+/// This is a separate type rather than an `And` impl for tuples, as
+/// `And<(A, B)>` would overlap with the impl for any `And<F: Filter>`, and a
+/// `Filter` impl for bare tuples could only mean one of `AllOf` and `AnyOf`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AllOf<T>(pub T);
+
+impl<T: ?Sized> Filter<T> for AllOf<()> {
+    #[inline]
+    fn matches(&self, _: &T) -> bool {
+        true
+    }
+}
+
+impl Optimizable for AllOf<()> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+impl TruthyDefault for AllOf<()> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self(())
+    }
+}
+
+impl Cost for AllOf<()> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        0.0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        1.0
+    }
+}
+
+macro_rules! tuple {
+    ($($F:ident),+) => {
+        impl<$($F: Filter<T>,)+ T: ?Sized> Filter<T> for AllOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn matches(&self, obj: &T) -> bool {
+                let ($($F,)+) = &self.0;
+                $($F.matches(obj))&&+
+            }
+        }
+
+        impl<$($F: Optimizable,)+> Optimizable for AllOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn as_bool(&self) -> Option<bool> {
+                let ($($F,)+) = &self.0;
+                let mut all = Some(true);
+                $(
+                    match $F.as_bool() {
+                        Some(false) => return Some(false),
+                        None => all = None,
+                        Some(true) => {}
+                    }
+                )+
+                all
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn optimize(&mut self) {
+                let ($($F,)+) = &mut self.0;
+                $($F.optimize();)+
+            }
+        }
+
+        impl<$($F: Cost,)+> Cost for AllOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn cost(&self) -> f64 {
+                let ($($F,)+) = &self.0;
+                cost::sequence([$($F as &dyn Cost,)+], false).0
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn selectivity(&self) -> f64 {
+                let ($($F,)+) = &self.0;
+                cost::sequence([$($F as &dyn Cost,)+], false).1
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn reorder(&mut self) {
+                let ($($F,)+) = &mut self.0;
+                $($F.reorder();)+
+            }
+        }
+
+        impl<$($F: TruthyDefault,)+> TruthyDefault for AllOf<($($F,)+)> {
+            #[inline]
+            fn truthy_default() -> Self {
+                Self(($($F::truthy_default(),)+))
+            }
+        }
+
+        impl<$($F: FalsyDefault,)+> FalsyDefault for AllOf<($($F,)+)> {
+            #[inline]
+            fn falsy_default() -> Self {
+                Self(($($F::falsy_default(),)+))
+            }
+        }
+    };
+}
+
+tuple!(A);
+tuple!(A, B);
+tuple!(A, B, C);
+tuple!(A, B, C, D);
+tuple!(A, B, C, D, E);
+tuple!(A, B, C, D, E, F);
+tuple!(A, B, C, D, E, F, G);
+tuple!(A, B, C, D, E, F, G, H);
+tuple!(A, B, C, D, E, F, G, H, I);
+tuple!(A, B, C, D, E, F, G, H, I, J);
+tuple!(A, B, C, D, E, F, G, H, I, J, K);
+tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::super::{from_fn, Range, TestFilter, TextFilter};
+    use super::*;
+
+    #[test]
+    fn matches() {
+        let f = AllOf((
+            TextFilter::starts_with("a"),
+            TextFilter::ends_with("z"),
+            from_fn(|s: &str| s.len() > 2),
+        ));
+
+        assert!(f.matches("abz"));
+        assert!(!f.matches("az"));
+        assert!(!f.matches("abc"));
+        assert!(AllOf(()).matches(&()));
+        assert!(!AllOf((TestFilter::None, TestFilter::Panic)).matches(&()));
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(AllOf(()).as_bool(), Some(true));
+        assert_eq!(AllOf((Range::lt(1), Range::gt(5))).as_bool(), None);
+        assert_eq!(
+            AllOf((TestFilter::Panic, TestFilter::None)).as_bool(),
+            Some(false)
+        );
+        assert_eq!(
+            AllOf::<(TestFilter, TestFilter, TestFilter)>::truthy_default().as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            AllOf::<(TestFilter, TestFilter)>::falsy_default().as_bool(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn cost() {
+        assert_eq!(AllOf(()).cost(), 0.0);
+        let f = AllOf((TestFilter::None, Range::lt(1), TestFilter::Panic));
+        assert_eq!(f.cost(), 1.0);
+        assert_eq!(f.selectivity(), 0.0);
+        let f = AllOf((TestFilter::Any, &TestFilter::Panic));
+        assert_eq!(f.cost(), 2.0);
+        assert_eq!(f.selectivity(), 0.5);
+    }
+}
@@ -1,6 +1,15 @@
-use super::{FalsyDefault, Filter, Optimizable, TruthyDefault};
-
-#[derive(Debug)]
+use super::cost;
+use super::display::{fmt_operands, Infix, Precedence};
+use super::{
+    Cost, Explain, Explanation, FalsyDefault, Filter, Optimizable, TriFilter, Truth, TruthyDefault,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct And<F>(Vec<F>);
 
 impl<F> From<Vec<F>> for And<F> {
@@ -32,6 +41,81 @@ impl<F: Filter<T>, T: ?Sized> Filter<T> for And<F> {
     }
 }
 
+impl<F: TriFilter<T>, T: ?Sized> TriFilter<T> for And<F> {
+    #[inline]
+    fn eval(&self, obj: &T) -> Truth {
+        let mut result = Truth::True;
+        for f in &self.0 {
+            match f.eval(obj) {
+                Truth::False => return Truth::False,
+                Truth::Unknown => result = Truth::Unknown,
+                Truth::True => {}
+            }
+        }
+        result
+    }
+}
+
+impl<F: Explain<T>, T: ?Sized> Explain<T> for And<F> {
+    fn explain(&self, obj: &T) -> Explanation {
+        let mut children = Vec::with_capacity(self.0.len());
+        let mut decided = false;
+        for f in &self.0 {
+            if decided {
+                children.push(Explanation::skipped(f.describe()));
+                continue;
+            }
+            let e = f.explain(obj);
+            decided = !e.matched();
+            children.push(e);
+        }
+        Explanation::new(!decided, self.describe()).with_children(children)
+    }
+
+    fn describe(&self) -> String {
+        format!("all of {}", self.0.len())
+    }
+}
+
+impl<F: Cost> Cost for And<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost::sequence(&self.0, false).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost::sequence(&self.0, false).1
+    }
+
+    /// Sorts the operands so those most likely to short circuit for their
+    /// cost run first.
+    #[inline]
+    fn reorder(&mut self) {
+        cost::sort(&mut self.0, false);
+    }
+}
+
+impl<F: Infix> std::fmt::Display for And<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.as_slice() {
+            [] => f.write_str("and()"),
+            [only] => write!(f, "and({only})"),
+            operands => fmt_operands(f, operands, " & ", Precedence::And),
+        }
+    }
+}
+
+impl<F: Infix> Infix for And<F> {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        match self.0.as_slice() {
+            [] | [_] => Precedence::Atom,
+            _ => Precedence::And,
+        }
+    }
+}
+
 impl<F: Optimizable + FalsyDefault> Optimizable for And<F> {
     #[inline]
     fn as_bool(&self) -> Option<bool> {
@@ -65,6 +149,7 @@ impl<F: Optimizable + FalsyDefault> Optimizable for And<F> {
 
         // if any filter is unconditionally false, the whole filter is false
         if short_circuit {
+            #[allow(clippy::swap_with_temporary)]
             std::mem::swap(self, &mut Self::falsy_default());
         }
     }
@@ -85,6 +170,7 @@ impl<F: FalsyDefault> FalsyDefault for And<F> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::super::TestFilter;
     use super::*;
@@ -124,4 +210,28 @@ mod tests {
 
         assert_eq!(f.as_bool().unwrap(), false);
     }
+
+    #[test]
+    fn kleene() {
+        let f = And::from(vec![TestFilter::Any, TestFilter::Panic]);
+        assert_eq!(f.eval(&()), Truth::Unknown);
+
+        let f = And::from(vec![TestFilter::Panic, TestFilter::None]);
+        assert_eq!(f.eval(&()), Truth::False);
+
+        let f = And::from(vec![TestFilter::Any, TestFilter::Any]);
+        assert_eq!(f.eval(&()), Truth::True);
+    }
+
+    #[test]
+    fn explain() {
+        let f = And::from(vec![TestFilter::Any, TestFilter::None, TestFilter::Panic]);
+        let e = f.explain(&());
+
+        assert!(!e.matched());
+        assert_eq!(
+            e.to_string(),
+            "failed: all of 3\n  matched: Any\n  failed: None\n  skipped: Panic\n"
+        );
+    }
 }
@@ -0,0 +1,173 @@
+use super::cost;
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if any filter of a tuple matches.
+///
+/// Unlike [`Or`](super::Or), the filters can have different types and
+/// don't need to be allocated.
+///
+/// This is a separate type rather than an `Or` impl for tuples, as
+/// `Or<(A, B)>` would overlap with the impl for any `Or<F: Filter>`, and a
+/// `Filter` impl for bare tuples could only mean one of `AllOf` and `AnyOf`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnyOf<T>(pub T);
+
+impl<T: ?Sized> Filter<T> for AnyOf<()> {
+    #[inline]
+    fn matches(&self, _: &T) -> bool {
+        false
+    }
+}
+
+impl Optimizable for AnyOf<()> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Some(false)
+    }
+}
+
+impl FalsyDefault for AnyOf<()> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self(())
+    }
+}
+
+impl Cost for AnyOf<()> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        0.0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        0.0
+    }
+}
+
+macro_rules! tuple {
+    ($($F:ident),+) => {
+        impl<$($F: Filter<T>,)+ T: ?Sized> Filter<T> for AnyOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn matches(&self, obj: &T) -> bool {
+                let ($($F,)+) = &self.0;
+                $($F.matches(obj))||+
+            }
+        }
+
+        impl<$($F: Optimizable,)+> Optimizable for AnyOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn as_bool(&self) -> Option<bool> {
+                let ($($F,)+) = &self.0;
+                let mut any = Some(false);
+                $(
+                    match $F.as_bool() {
+                        Some(true) => return Some(true),
+                        None => any = None,
+                        Some(false) => {}
+                    }
+                )+
+                any
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn optimize(&mut self) {
+                let ($($F,)+) = &mut self.0;
+                $($F.optimize();)+
+            }
+        }
+
+        impl<$($F: Cost,)+> Cost for AnyOf<($($F,)+)> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn cost(&self) -> f64 {
+                let ($($F,)+) = &self.0;
+                cost::sequence([$($F as &dyn Cost,)+], true).0
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn selectivity(&self) -> f64 {
+                let ($($F,)+) = &self.0;
+                cost::sequence([$($F as &dyn Cost,)+], true).1
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn reorder(&mut self) {
+                let ($($F,)+) = &mut self.0;
+                $($F.reorder();)+
+            }
+        }
+
+        impl<$($F: TruthyDefault,)+> TruthyDefault for AnyOf<($($F,)+)> {
+            #[inline]
+            fn truthy_default() -> Self {
+                Self(($($F::truthy_default(),)+))
+            }
+        }
+
+        impl<$($F: FalsyDefault,)+> FalsyDefault for AnyOf<($($F,)+)> {
+            #[inline]
+            fn falsy_default() -> Self {
+                Self(($($F::falsy_default(),)+))
+            }
+        }
+    };
+}
+
+tuple!(A);
+tuple!(A, B);
+tuple!(A, B, C);
+tuple!(A, B, C, D);
+tuple!(A, B, C, D, E);
+tuple!(A, B, C, D, E, F);
+tuple!(A, B, C, D, E, F, G);
+tuple!(A, B, C, D, E, F, G, H);
+tuple!(A, B, C, D, E, F, G, H, I);
+tuple!(A, B, C, D, E, F, G, H, I, J);
+tuple!(A, B, C, D, E, F, G, H, I, J, K);
+tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::super::{from_fn, Range, TestFilter, TextFilter};
+    use super::*;
+
+    #[test]
+    fn matches() {
+        let f = AnyOf((
+            TextFilter::starts_with("a"),
+            TextFilter::ends_with("z"),
+            from_fn(|s: &str| s.len() > 2),
+        ));
+
+        assert!(f.matches("ab"));
+        assert!(f.matches("bz"));
+        assert!(f.matches("bcd"));
+        assert!(!f.matches("bc"));
+        assert!(!AnyOf(()).matches(&()));
+        assert!(AnyOf((TestFilter::Any, TestFilter::Panic)).matches(&()));
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(AnyOf(()).as_bool(), Some(false));
+        assert_eq!(AnyOf((Range::lt(1), Range::gt(5))).as_bool(), None);
+        assert_eq!(
+            AnyOf((TestFilter::Panic, TestFilter::Any)).as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            AnyOf::<(TestFilter, TestFilter, TestFilter)>::truthy_default().as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            AnyOf::<(TestFilter, TestFilter)>::falsy_default().as_bool(),
+            Some(false)
+        );
+    }
+}
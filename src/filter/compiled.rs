@@ -0,0 +1,251 @@
+use std::fmt;
+
+use super::{Cost, DataFilter, Filter};
+use datalink::Data;
+
+/// A [`DataFilter`] lowered to a flat program, created with
+/// [`DataFilter::compile`].
+///
+/// `And`, `Or` and `Not` become jumps over a single sequence of leaf tests,
+/// so evaluation doesn't recurse. Leaves, including the [`LinkFilter`] of
+/// [`DataFilter::Linked`], are evaluated like the tree.
+///
+/// [`LinkFilter`]: super::LinkFilter
+#[derive(Debug, Clone)]
+pub struct CompiledFilter {
+    program: Vec<Instr>,
+    /// The [`Cost`] of the filter the program was lowered from.
+    cost: f64,
+    selectivity: f64,
+}
+
+#[derive(Debug, Clone)]
+enum Instr {
+    /// Sets the result.
+    Const(bool),
+    /// Sets the result to whether the leaf matches, inverted if `negate`.
+    Test { filter: DataFilter, negate: bool },
+    /// Continues at `target` if the result is `value`.
+    JumpIf { value: bool, target: usize },
+}
+
+impl CompiledFilter {
+    pub(crate) fn new(filter: &DataFilter) -> Self {
+        let mut compiled = Self {
+            program: Vec::new(),
+            cost: filter.cost(),
+            selectivity: filter.selectivity(),
+        };
+        compiled.lower(filter, false);
+        compiled.thread_jumps();
+        compiled
+    }
+
+    /// Number of instructions in the program.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.program.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.program.is_empty()
+    }
+
+    /// Appends instructions computing `filter`, or its negation if `negate`.
+    fn lower(&mut self, filter: &DataFilter, negate: bool) {
+        use DataFilter as E;
+        match filter {
+            E::Any => self.program.push(Instr::Const(!negate)),
+            E::None => self.program.push(Instr::Const(negate)),
+            E::Not(f) => self.lower(&f.0, !negate),
+            // negated, a conjunction is decided by the first `true`
+            E::And(operands) => self.lower_sequence(operands, true, negate),
+            E::Or(operands) => self.lower_sequence(operands, false, negate),
+            f => self.program.push(Instr::Test {
+                filter: f.clone(),
+                negate,
+            }),
+        }
+    }
+
+    /// Lowers the operands of an `And` (`all`) or `Or`, jumping to the end
+    /// as soon as one of them decides the result.
+    fn lower_sequence(&mut self, operands: &[DataFilter], all: bool, negate: bool) {
+        let Some((last, init)) = operands.split_last() else {
+            self.program.push(Instr::Const(all != negate));
+            return;
+        };
+        let decisive = all == negate;
+        let mut jumps = Vec::with_capacity(init.len());
+        for f in init {
+            self.lower(f, negate);
+            jumps.push(self.program.len());
+            self.program.push(Instr::JumpIf {
+                value: decisive,
+                target: 0,
+            });
+        }
+        self.lower(last, negate);
+        let end = self.program.len();
+        for jump in jumps {
+            if let Instr::JumpIf { target, .. } = &mut self.program[jump] {
+                *target = end;
+            }
+        }
+    }
+
+    /// Retargets jumps landing on other jumps, so nested operators short
+    /// circuit in a single step.
+    fn thread_jumps(&mut self) {
+        for i in 0..self.program.len() {
+            let Instr::JumpIf { value, mut target } = self.program[i] else {
+                continue;
+            };
+            // all jumps go forward, so this terminates
+            while let Some(&Instr::JumpIf {
+                value: next,
+                target: next_target,
+            }) = self.program.get(target)
+            {
+                target = if next == value {
+                    next_target
+                } else {
+                    target + 1
+                };
+            }
+            self.program[i] = Instr::JumpIf { value, target };
+        }
+    }
+}
+
+impl<D: Data + ?Sized> Filter<D> for CompiledFilter {
+    fn matches(&self, d: &D) -> bool {
+        let mut result = true;
+        let mut pc = 0;
+        while let Some(instr) = self.program.get(pc) {
+            pc += 1;
+            match instr {
+                Instr::Const(b) => result = *b,
+                Instr::Test { filter, negate } => result = filter.matches(d) != *negate,
+                Instr::JumpIf { value, target } => {
+                    if result == *value {
+                        pc = *target;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Jumps skip the same tests the tree would short circuit, so the program
+/// costs as much as the filter it was lowered from.
+impl Cost for CompiledFilter {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.selectivity
+    }
+}
+
+/// Writes the program one instruction per line, like `2: jump_if false 5`.
+impl fmt::Display for CompiledFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, instr) in self.program.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{i}: ")?;
+            match instr {
+                Instr::Const(b) => write!(f, "const {b}")?,
+                Instr::Test {
+                    filter,
+                    negate: false,
+                } => write!(f, "test {filter}")?,
+                Instr::Test {
+                    filter,
+                    negate: true,
+                } => write!(f, "test_not {filter}")?,
+                Instr::JumpIf { value, target } => write!(f, "jump_if {value} {target}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextFilter;
+    use datalink::value::ValueBuiler;
+
+    struct Text(&'static str);
+
+    impl Data for Text {
+        fn provide_value<'d>(&'d self, value: &mut dyn ValueBuiler<'d>) {
+            value.str(self.0.into());
+        }
+    }
+
+    fn contains(s: &str) -> DataFilter {
+        DataFilter::text(TextFilter::contains(s))
+    }
+
+    /// Checks that `f` compiles to `program` and matches like the tree.
+    fn check(f: &DataFilter, program: &str) {
+        let compiled = CompiledFilter::new(f);
+        assert_eq!(compiled.to_string(), program);
+        for text in ["", "a", "b", "ab", "c"] {
+            assert_eq!(
+                compiled.matches(&Text(text)),
+                f.matches(&Text(text)),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn not() {
+        let (a, b) = (contains("a"), contains("b"));
+        check(
+            &!(a.clone() & b.clone()),
+            "0: test_not text(contains(\"a\"))\n1: jump_if true 3\n2: test_not text(contains(\"b\"))",
+        );
+        check(
+            &!(a | b),
+            "0: test_not text(contains(\"a\"))\n1: jump_if false 3\n2: test_not text(contains(\"b\"))",
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let (a, b, c) = (contains("a"), contains("b"), contains("c"));
+        check(
+            &((a.clone() & b.clone()) | c.clone()),
+            "0: test text(contains(\"a\"))\n1: jump_if false 4\n2: test text(contains(\"b\"))\n3: jump_if true 5\n4: test text(contains(\"c\"))",
+        );
+        check(
+            &!(!(a & b) & c),
+            "0: test text(contains(\"a\"))\n1: jump_if false 4\n2: test text(contains(\"b\"))\n3: jump_if true 5\n4: test_not text(contains(\"c\"))",
+        );
+    }
+
+    #[test]
+    fn empty() {
+        check(&DataFilter::And(Vec::new().into()), "0: const true");
+        check(&DataFilter::Or(Vec::new().into()), "0: const false");
+        check(&!DataFilter::And(Vec::new().into()), "0: const false");
+        check(&!DataFilter::Or(Vec::new().into()), "0: const true");
+        check(
+            &(contains("a") & DataFilter::Or(Vec::new().into())),
+            "0: test text(contains(\"a\"))\n1: jump_if false 3\n2: const false",
+        );
+    }
+}
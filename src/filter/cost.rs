@@ -0,0 +1,337 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Estimates how expensive a filter is, so `And` and `Or` can run cheap and
+/// decisive operands first.
+pub trait Cost {
+    /// The relative cost of evaluating the filter once, with a single
+    /// comparison costing about 1.
+    fn cost(&self) -> f64;
+
+    /// The estimated fraction of objects the filter matches, from 0 to 1.
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        0.5
+    }
+
+    /// Reorders the operands of nested `And`s and `Or`s to lower the
+    /// expected cost, without changing what the filter matches.
+    #[inline]
+    fn reorder(&mut self) {}
+}
+
+impl Cost for bool {
+    #[inline]
+    fn cost(&self) -> f64 {
+        0.0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        if *self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<F: Cost + ?Sized> Cost for Box<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.as_ref().cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.as_ref().selectivity()
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.as_mut().reorder();
+    }
+}
+
+/// A shared reference can't reorder the filter, so [`Cost::reorder`] does
+/// nothing, unlike for `&mut F`.
+impl<F: Cost + ?Sized> Cost for &F {
+    #[inline]
+    fn cost(&self) -> f64 {
+        (**self).cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        (**self).selectivity()
+    }
+}
+
+impl<F: Cost + ?Sized> Cost for &mut F {
+    #[inline]
+    fn cost(&self) -> f64 {
+        (**self).cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        (**self).selectivity()
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        (**self).reorder();
+    }
+}
+
+impl<F: Cost + ?Sized> Cost for Rc<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.as_ref().cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.as_ref().selectivity()
+    }
+
+    /// Only reorders the filter if it isn't shared.
+    #[inline]
+    fn reorder(&mut self) {
+        if let Some(f) = Rc::get_mut(self) {
+            f.reorder();
+        }
+    }
+}
+
+impl<F: Cost + ?Sized> Cost for Arc<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.as_ref().cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.as_ref().selectivity()
+    }
+
+    /// Only reorders the filter if it isn't shared.
+    #[inline]
+    fn reorder(&mut self) {
+        if let Some(f) = Arc::get_mut(self) {
+            f.reorder();
+        }
+    }
+}
+
+impl<F: Cost + Clone> Cost for Cow<'_, F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.as_ref().cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.as_ref().selectivity()
+    }
+
+    /// Only reorders owned filters, to avoid cloning borrowed ones.
+    #[inline]
+    fn reorder(&mut self) {
+        if let Cow::Owned(f) = self {
+            f.reorder();
+        }
+    }
+}
+
+/// `None` matches every object without evaluating anything.
+impl<F: Cost> Cost for Option<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.as_ref().map_or(0.0, Cost::cost)
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        self.as_ref().map_or(1.0, Cost::selectivity)
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        if let Some(f) = self {
+            f.reorder();
+        }
+    }
+}
+
+/// The expected cost and selectivity of operands evaluated in order until
+/// one of them returns `decisive`.
+pub(super) fn sequence<'a, F: Cost + ?Sized + 'a>(
+    operands: impl IntoIterator<Item = &'a F>,
+    decisive: bool,
+) -> (f64, f64) {
+    // the chance that evaluation gets this far
+    let mut reached = 1.0;
+    let mut cost = 0.0;
+    for f in operands {
+        cost += reached * f.cost();
+        let undecided = if decisive {
+            1.0 - f.selectivity()
+        } else {
+            f.selectivity()
+        };
+        reached *= undecided.clamp(0.0, 1.0);
+    }
+    let selectivity = if decisive { 1.0 - reached } else { reached };
+    (cost, selectivity)
+}
+
+/// Reorders operands evaluated until one of them returns `decisive`, so the
+/// expected cost is minimal if they're independent.
+pub(super) fn sort<F: Cost>(operands: &mut [F], decisive: bool) {
+    for f in operands.iter_mut() {
+        f.reorder();
+    }
+    // running `f` first is better if its cost per chance of deciding the
+    // outcome is lower
+    let rank = |f: &F| {
+        let decides = if decisive {
+            f.selectivity()
+        } else {
+            1.0 - f.selectivity()
+        };
+        let cost = f.cost();
+        if cost > 0.0 {
+            cost / decides.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    // ranks are never negative, so their bits order like their values
+    operands.sort_by_cached_key(|f| rank(f).to_bits());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{And, Filter, Or, TestFilter};
+
+    /// Counts how often the wrapped filter is evaluated.
+    struct Counting<'a> {
+        filter: TestFilter,
+        cost: f64,
+        evals: &'a Cell<usize>,
+    }
+
+    impl<T> Filter<T> for Counting<'_> {
+        fn matches(&self, obj: &T) -> bool {
+            self.evals.set(self.evals.get() + 1);
+            self.filter.matches(obj)
+        }
+    }
+
+    impl Cost for Counting<'_> {
+        fn cost(&self) -> f64 {
+            self.cost
+        }
+
+        fn selectivity(&self) -> f64 {
+            self.filter.selectivity()
+        }
+    }
+
+    fn evals<F: Filter<()>>(f: &F, evals: &Cell<usize>) -> usize {
+        evals.set(0);
+        for _ in 0..100 {
+            f.matches(&());
+        }
+        evals.get()
+    }
+
+    #[test]
+    fn and() {
+        let count = Cell::new(0);
+        let counting = |filter, cost| Counting {
+            filter,
+            cost,
+            evals: &count,
+        };
+        let mut f = And::from(vec![
+            counting(TestFilter::Any, 10.0),
+            counting(TestFilter::Any, 5.0),
+            counting(TestFilter::None, 1.0),
+        ]);
+        assert_eq!(f.cost(), 16.0);
+        assert_eq!(evals(&f, &count), 300);
+
+        f.reorder();
+        assert_eq!(f[0].filter, TestFilter::None);
+        assert_eq!(f.cost(), 1.0);
+        assert_eq!(evals(&f, &count), 100);
+    }
+
+    #[test]
+    fn or() {
+        let count = Cell::new(0);
+        let counting = |filter, cost| Counting {
+            filter,
+            cost,
+            evals: &count,
+        };
+        let mut f = Or::from(vec![
+            counting(TestFilter::None, 1.0),
+            counting(TestFilter::None, 1.0),
+            counting(TestFilter::Any, 3.0),
+        ]);
+        assert_eq!(evals(&f, &count), 300);
+
+        f.reorder();
+        assert_eq!(f[0].filter, TestFilter::Any);
+        assert_eq!(evals(&f, &count), 100);
+    }
+
+    #[test]
+    fn nested() {
+        let mut f = And::from(vec![
+            Or::from(vec![TestFilter::Panic, TestFilter::Any]),
+            Or::from(vec![TestFilter::None]),
+        ]);
+        assert_eq!(f.selectivity(), 0.0);
+
+        f.reorder();
+        assert_eq!(f[0][0], TestFilter::None);
+        assert_eq!(f[1][0], TestFilter::Any);
+        assert!(!f.matches(&()));
+    }
+
+    #[test]
+    fn wrappers() {
+        use std::borrow::Cow;
+        use std::rc::Rc;
+
+        let f = Or::from(vec![TestFilter::None, TestFilter::Any]);
+        assert_eq!(Cost::cost(&&f), f.cost());
+        assert_eq!(None::<TestFilter>.cost(), 0.0);
+        assert_eq!(None::<TestFilter>.selectivity(), 1.0);
+        assert_eq!(Some(TestFilter::None).selectivity(), 0.0);
+
+        let mut borrowed = f.clone();
+        Cost::reorder(&mut &borrowed);
+        assert_eq!(borrowed[0], TestFilter::None);
+        Cost::reorder(&mut &mut borrowed);
+        assert_eq!(borrowed[0], TestFilter::Any);
+
+        let mut rc = Rc::new(f.clone());
+        rc.reorder();
+        assert_eq!(rc[0], TestFilter::Any);
+        let mut cow = Cow::Borrowed(&f);
+        cow.reorder();
+        assert_eq!(cow[0], TestFilter::None);
+        let mut cow: Cow<'_, Or<TestFilter>> = Cow::Owned(f);
+        cow.reorder();
+        assert_eq!(cow[0], TestFilter::Any);
+    }
+}
@@ -1,11 +1,30 @@
 use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, RandomState};
 
+use super::normalize::{self, Connectives, Node};
 use super::{
-    And, FalsyDefault, Filter, LinkFilter, Not, Optimizable, Or, TextFilter, TruthyDefault,
+    And, CompiledFilter, Cost, Explain, Explanation, FalsyDefault, Filter, Infix, LinkFilter,
+    Normalize, Not, Optimizable, Or, Precedence, Range, TextFilter, TextSet, TooLarge, TriFilter,
+    Truth, TruthyDefault,
 };
 use datalink::{id::ID, BoxedData, Data};
 
-#[derive(Default, Debug)]
+/// A filter on [`Data`].
+///
+/// With the `serde` feature, filters are serialized externally tagged with
+/// snake case variant names, e.g. `{"and":[{"text":"foo"},{"not":"unique"}]}`.
+/// Unit variants are plain strings, IDs are strings in their `Display` form,
+/// ranges are maps like `{"ge":1,"lt":5}` and text sets are sequences of text
+/// filters. Infinite and NaN float bounds fail to serialize, as JSON would
+/// turn them into `null`. Unknown variants, e.g. from a newer version, fail to deserialize
+/// with an "unknown variant" error.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
 pub enum DataFilter {
     #[default]
@@ -14,13 +33,46 @@ pub enum DataFilter {
     And(And<DataFilter>),
     Not(Box<Not<DataFilter>>),
     Text(TextFilter),
+    TextSet(TextSet),
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "super::range::serialize_finite")
+    )]
+    Number(Range<f64>),
+    Int(Range<i128>),
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "super::range::serialize_finite")
+    )]
+    Float(Range<f64>),
+    Bool(bool),
     Unique,
+    #[cfg_attr(feature = "serde", serde(with = "id_repr"))]
     Id(ID),
+    #[cfg_attr(feature = "serde", serde(with = "id_repr"))]
     NotId(ID),
     Linked(Box<LinkFilter>),
     None,
 }
 
+/// Serializes IDs in their string form.
+#[cfg(feature = "serde")]
+mod id_repr {
+    use datalink::id::ID;
+    use serde::de::{Error, Unexpected};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(id: &ID, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(id)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ID, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| D::Error::invalid_value(Unexpected::Str(&s), &"an id"))
+    }
+}
+
 impl DataFilter {
     #[inline]
     #[must_use]
@@ -37,6 +89,34 @@ impl DataFilter {
     pub fn text(f: impl Into<TextFilter>) -> Self {
         Self::Text(f.into())
     }
+    /// Matches data with a numeric value in `range`.
+    ///
+    /// Integers and floats are compared by their `f64` value, see
+    /// [`DataFilter::int`] and [`DataFilter::float`] to match only one kind.
+    #[inline]
+    #[must_use]
+    pub fn number(range: impl Into<Range<f64>>) -> Self {
+        Self::Number(range.into())
+    }
+    /// Matches data with an integer value in `range`.
+    #[inline]
+    #[must_use]
+    pub fn int(range: impl Into<Range<i128>>) -> Self {
+        Self::Int(range.into())
+    }
+    /// Matches data with a floating point value in `range`, ignoring
+    /// integers unlike [`DataFilter::number`].
+    #[inline]
+    #[must_use]
+    pub fn float(range: impl Into<Range<f64>>) -> Self {
+        Self::Float(range.into())
+    }
+    /// Matches data with the boolean value `value`.
+    #[inline]
+    #[must_use]
+    pub const fn bool(value: bool) -> Self {
+        Self::Bool(value)
+    }
     #[inline]
     #[must_use]
     pub const fn unique() -> Self {
@@ -91,9 +171,163 @@ impl DataFilter {
             _ => Self::Or(vec![self, f.into()].into()),
         }
     }
+
+    /// Lowers the filter to a flat program, which evaluates without
+    /// recursing. Optimize the filter first, so the program doesn't test
+    /// constant operands.
+    #[must_use]
+    pub fn compile(&self) -> CompiledFilter {
+        CompiledFilter::new(self)
+    }
+
+    /// Whether both filters are equal after optimizing and normalizing them,
+    /// so e.g. the order of `And` and `Or` operands doesn't matter.
+    #[must_use]
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    fn canonical(&self) -> Self {
+        let mut f = self.clone();
+        f.optimize();
+        normalize::canonicalize(f)
+    }
 }
+
+#[inline]
+fn float_bits(range: &Range<f64>) -> Range<u64> {
+    use std::ops::RangeBounds;
+    let start = range.start_bound().map(|f| f.to_bits());
+    let end = range.end_bound().map(|f| f.to_bits());
+    Range::new(start, end)
+}
+
+impl Connectives for DataFilter {
+    #[inline]
+    fn and(operands: Vec<Self>) -> Self {
+        Self::And(operands.into())
+    }
+    #[inline]
+    fn or(operands: Vec<Self>) -> Self {
+        Self::Or(operands.into())
+    }
+    #[inline]
+    fn not(self) -> Self {
+        !self
+    }
+    #[inline]
+    fn into_node(self) -> Node<Self> {
+        match self {
+            Self::And(mut and) => Node::And(std::mem::take(&mut *and)),
+            Self::Or(mut or) => Node::Or(std::mem::take(&mut *or)),
+            Self::Not(not) => Node::Not(not.0),
+            f => Node::Leaf(f),
+        }
+    }
+    #[inline]
+    fn negate_leaf(self) -> Self {
+        match self {
+            Self::Any => Self::None,
+            Self::None => Self::Any,
+            Self::Id(id) => Self::NotId(id),
+            Self::NotId(id) => Self::Id(id),
+            f => !f,
+        }
+    }
+    #[inline]
+    fn flatten_nested(self) -> Self {
+        match self {
+            Self::Linked(f) => Self::Linked(Box::new(f.flatten())),
+            f => f,
+        }
+    }
+    #[inline]
+    fn nnf_nested(self) -> Self {
+        match self {
+            Self::Linked(f) => Self::Linked(Box::new(f.to_nnf())),
+            f => f,
+        }
+    }
+    #[inline]
+    fn canonicalize_nested(self) -> Self {
+        match self {
+            Self::Linked(f) => Self::Linked(Box::new(normalize::canonicalize(*f))),
+            Self::TextSet(set) if set.len() == 1 => {
+                set.into_iter().next().map_or(Self::None, Self::Text)
+            }
+            f => f,
+        }
+    }
+}
+
+impl Normalize for DataFilter {
+    #[inline]
+    fn flatten(self) -> Self {
+        normalize::flatten(self)
+    }
+    #[inline]
+    fn to_nnf(self) -> Self {
+        normalize::to_nnf(self)
+    }
+    #[inline]
+    fn to_dnf(self, limit: usize) -> Result<Self, TooLarge> {
+        normalize::to_dnf(self, limit)
+    }
+    #[inline]
+    fn to_cnf(self, limit: usize) -> Result<Self, TooLarge> {
+        normalize::to_cnf(self, limit)
+    }
+}
+
+/// Float ranges are compared by their bit patterns, so every filter is equal
+/// to itself, even if it contains NaN.
+impl PartialEq for DataFilter {
+    fn eq(&self, other: &Self) -> bool {
+        use DataFilter as E;
+        match (self, other) {
+            (E::Any, E::Any) | (E::None, E::None) | (E::Unique, E::Unique) => true,
+            (E::Or(a), E::Or(b)) => a == b,
+            (E::And(a), E::And(b)) => a == b,
+            (E::Not(a), E::Not(b)) => a == b,
+            (E::Text(a), E::Text(b)) => a == b,
+            (E::TextSet(a), E::TextSet(b)) => a == b,
+            (E::Number(a), E::Number(b)) | (E::Float(a), E::Float(b)) => {
+                float_bits(a) == float_bits(b)
+            }
+            (E::Int(a), E::Int(b)) => a == b,
+            (E::Bool(a), E::Bool(b)) => a == b,
+            (E::Id(a), E::Id(b)) | (E::NotId(a), E::NotId(b)) => a == b,
+            (E::Linked(a), E::Linked(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for DataFilter {}
+
+impl std::hash::Hash for DataFilter {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        use DataFilter as E;
+        std::mem::discriminant(self).hash(state);
+        match self {
+            E::Any | E::None | E::Unique => {}
+            E::Or(f) => f.hash(state),
+            E::And(f) => f.hash(state),
+            E::Not(f) => f.hash(state),
+            E::Text(f) => f.hash(state),
+            E::TextSet(f) => f.hash(state),
+            E::Number(f) | E::Float(f) => float_bits(f).hash(state),
+            E::Int(f) => f.hash(state),
+            E::Bool(b) => b.hash(state),
+            E::Id(id) | E::NotId(id) => id.hash(state),
+            E::Linked(f) => f.hash(state),
+        }
+    }
+}
+
 impl<D: Data + ?Sized> Filter<D> for DataFilter {
     #[inline]
+    #[allow(clippy::nonminimal_bool)]
     fn matches(&self, d: &D) -> bool {
         use DataFilter as E;
         match self {
@@ -147,26 +381,592 @@ impl<D: Data + ?Sized> Filter<D> for DataFilter {
                 let _ = d.borrow().provide_links(&mut searcher);
                 searcher.0
             }
-            E::Text(f) => {
-                enum Matcher<'a> {
-                    Found,
-                    Selecting(&'a TextFilter),
+            E::Text(f) => text_eval(f, d).is_true(),
+            E::TextSet(f) => text_eval(f, d).is_true(),
+            E::Number(f) => number_eval(f, d).is_true(),
+            E::Int(f) => int_eval(f, d).is_true(),
+            E::Float(f) => float_eval(f, d).is_true(),
+            E::Bool(b) => bool_eval(*b, d).is_true(),
+        }
+    }
+}
+
+impl<D: Data + ?Sized> TriFilter<D> for DataFilter {
+    /// Evaluates the filter, returning [`Truth::Unknown`] where the data
+    /// doesn't provide the value or links the filter asks for.
+    #[inline]
+    fn eval(&self, d: &D) -> Truth {
+        use DataFilter as E;
+        match self {
+            E::Any => Truth::True,
+            E::None => Truth::False,
+            E::And(and) => TriFilter::<D>::eval(and, d),
+            E::Or(or) => TriFilter::<D>::eval(or, d),
+            // data without an ID certainly doesn't have the one asked for
+            E::Id(_) | E::NotId(_) | E::Unique => Filter::<D>::matches(self, d).into(),
+            E::Not(f) => TriFilter::<D>::eval(f, d),
+            E::Linked(f) => {
+                // `None` until the first link was provided
+                struct Evaluator<'a>(Option<Truth>, &'a LinkFilter);
+                impl Evaluator<'_> {
+                    #[inline]
+                    fn record(&mut self, truth: Truth) -> datalink::links::Result {
+                        let truth = self.0.unwrap_or(Truth::False) | truth;
+                        self.0 = Some(truth);
+                        if truth.is_true() {
+                            datalink::links::BREAK
+                        } else {
+                            datalink::links::CONTINUE
+                        }
+                    }
                 }
-                impl datalink::value::ValueBuiler<'_> for Matcher<'_> {
-                    fn str(&mut self, value: std::borrow::Cow<'_, str>) {
-                        match self {
-                            Matcher::Selecting(f) if f.matches(value.as_ref()) => {
-                                *self = Matcher::Found
-                            }
-                            _ => {}
+                impl datalink::links::Links for Evaluator<'_> {
+                    #[inline]
+                    fn push(
+                        &mut self,
+                        target: BoxedData,
+                        key: Option<BoxedData>,
+                    ) -> datalink::links::Result {
+                        if let Some(key) = key {
+                            self.push_keyed(target, key)
+                        } else {
+                            self.push_unkeyed(target)
                         }
                     }
+                    #[inline]
+                    fn push_keyed(
+                        &mut self,
+                        target: BoxedData,
+                        key: BoxedData,
+                    ) -> datalink::links::Result {
+                        let truth = self.1.eval(&(key, target));
+                        self.record(truth)
+                    }
+                    #[inline]
+                    fn push_unkeyed(&mut self, target: BoxedData) -> datalink::links::Result {
+                        let truth = TriFilter::<BoxedData>::eval(self.1, &target);
+                        self.record(truth)
+                    }
+                }
+                let mut evaluator = Evaluator(None, f);
+                if d.borrow().provide_links(&mut evaluator).is_err() {
+                    return Truth::Unknown;
+                }
+                evaluator.0.unwrap_or(Truth::Unknown)
+            }
+            E::Text(f) => text_eval(f, d),
+            E::TextSet(f) => text_eval(f, d),
+            E::Number(f) => number_eval(f, d),
+            E::Int(f) => int_eval(f, d),
+            E::Float(f) => float_eval(f, d),
+            E::Bool(b) => bool_eval(*b, d),
+        }
+    }
+}
+
+impl<D: Data + ?Sized> Explain<D> for DataFilter {
+    fn explain(&self, d: &D) -> Explanation {
+        use DataFilter as E;
+        let leaf = |matched: bool, detail: String| Explanation::new(matched, detail);
+        match self {
+            E::Any | E::None => leaf(self.matches(d), self.description()),
+            E::And(and) => Explain::<D>::explain(and, d),
+            E::Or(or) => Explain::<D>::explain(or, d),
+            E::Not(f) => Explain::<D>::explain(&**f, d),
+            E::Id(id) | E::NotId(id) => {
+                let matched = self.matches(d);
+                match d.get_id() {
+                    Some(i) if i == *id => leaf(matched, format!("id is {id}")),
+                    Some(i) => leaf(matched, format!("id {i} is not {id}")),
+                    None => leaf(matched, "no id".to_owned()),
+                }
+            }
+            E::Unique => match d.get_id() {
+                Some(id) => leaf(true, format!("has id {id}")),
+                None => leaf(false, "no id".to_owned()),
+            },
+            E::Linked(f) => {
+                struct Explainer<'a>(Vec<Explanation>, &'a LinkFilter);
+                impl Explainer<'_> {
+                    #[inline]
+                    fn record(&mut self, e: Explanation) -> datalink::links::Result {
+                        let matched = e.matched();
+                        self.0.push(e);
+                        if matched {
+                            datalink::links::BREAK
+                        } else {
+                            datalink::links::CONTINUE
+                        }
+                    }
+                }
+                impl datalink::links::Links for Explainer<'_> {
+                    #[inline]
+                    fn push(
+                        &mut self,
+                        target: BoxedData,
+                        key: Option<BoxedData>,
+                    ) -> datalink::links::Result {
+                        if let Some(key) = key {
+                            self.push_keyed(target, key)
+                        } else {
+                            self.push_unkeyed(target)
+                        }
+                    }
+                    #[inline]
+                    fn push_keyed(
+                        &mut self,
+                        target: BoxedData,
+                        key: BoxedData,
+                    ) -> datalink::links::Result {
+                        let e = self.1.explain(&(key, target));
+                        self.record(e)
+                    }
+                    #[inline]
+                    fn push_unkeyed(&mut self, target: BoxedData) -> datalink::links::Result {
+                        let e = Explain::<BoxedData>::explain(self.1, &target);
+                        self.record(e)
+                    }
+                }
+                let mut explainer = Explainer(Vec::new(), f);
+                let _ = d.borrow().provide_links(&mut explainer);
+                let links = explainer.0;
+                let matched = links.iter().any(Explanation::matched);
+                let detail = match (links.is_empty(), matched) {
+                    (true, _) => "no links".to_owned(),
+                    (false, true) => format!("a link matched {}", f.description()),
+                    (false, false) => format!("no link matched {}", f.description()),
+                };
+                leaf(matched, detail).with_children(links)
+            }
+            E::Text(f) => {
+                let texts = Values::of(d).texts;
+                match texts.iter().find(|t| f.matches(t)).or(texts.first()) {
+                    Some(text) => {
+                        let mut e = f.explain(text);
+                        e.detail.insert_str(0, "text ");
+                        e
+                    }
+                    None => leaf(false, "no text value".to_owned()),
+                }
+            }
+            E::TextSet(f) => {
+                let texts = Values::of(d).texts;
+                let set = format!("set of {}", f.len());
+                match texts.iter().find(|t| f.matches(t)) {
+                    Some(text) => leaf(true, format!("text {text:?} is in {set}")),
+                    None if texts.is_empty() => leaf(false, "no text value".to_owned()),
+                    None => leaf(false, format!("text {} is not in {set}", texts.join(", "))),
+                }
+            }
+            E::Number(f) => {
+                let values = Values::of(d);
+                let numbers = [values.ints, values.floats].concat();
+                leaf_in_range("number", &numbers, number_eval(f, d), f)
+            }
+            E::Int(f) => leaf_in_range("int", &Values::of(d).ints, int_eval(f, d), f),
+            E::Float(f) => leaf_in_range("float", &Values::of(d).floats, float_eval(f, d), f),
+            E::Bool(b) => match bool_eval(*b, d) {
+                Truth::True => leaf(true, format!("bool is {b}")),
+                Truth::False => leaf(false, format!("bool is not {b}")),
+                Truth::Unknown => leaf(false, "no bool value".to_owned()),
+            },
+        }
+    }
+
+    #[inline]
+    fn describe(&self) -> String {
+        self.description()
+    }
+}
+
+impl DataFilter {
+    pub(crate) fn description(&self) -> String {
+        use DataFilter as E;
+        match self {
+            E::Any => "any".to_owned(),
+            E::None => "none".to_owned(),
+            E::And(and) => format!("all of {}", and.len()),
+            E::Or(or) => format!("any of {}", or.len()),
+            E::Not(f) => format!("not {}", f.0.description()),
+            E::Id(id) => format!("id {id}"),
+            E::NotId(id) => format!("id not {id}"),
+            E::Unique => "unique".to_owned(),
+            E::Linked(f) => format!("linked {}", f.description()),
+            E::Text(f) => format!("text {}", Explain::<str>::describe(f)),
+            E::TextSet(f) => format!("text in set of {}", f.len()),
+            E::Number(f) => format!("number in {}", f),
+            E::Int(f) => format!("int in {}", f),
+            E::Float(f) => format!("float in {}", f),
+            E::Bool(b) => format!("bool {b}"),
+        }
+    }
+}
+
+/// The values a data provides, formatted for explanations.
+#[derive(Default)]
+struct Values {
+    texts: Vec<String>,
+    ints: Vec<String>,
+    floats: Vec<String>,
+}
+
+impl Values {
+    fn of<D: Data + ?Sized>(d: &D) -> Self {
+        let mut values = Self::default();
+        d.borrow().provide_value(&mut values);
+        values
+    }
+}
+
+macro_rules! ints {
+    ($($ty:ident),*) => {$(
+        fn $ty(&mut self, value: $ty) {
+            self.ints.push(value.to_string());
+        }
+    )*};
+}
+
+impl datalink::value::ValueBuiler<'_> for Values {
+    ints!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+    fn f32(&mut self, value: f32) {
+        self.floats.push(value.to_string());
+    }
+    fn f64(&mut self, value: f64) {
+        self.floats.push(value.to_string());
+    }
+    fn str(&mut self, value: std::borrow::Cow<'_, str>) {
+        self.texts.push(value.into_owned());
+    }
+}
+
+fn leaf_in_range<T: std::fmt::Display>(
+    kind: &str,
+    values: &[String],
+    truth: Truth,
+    range: &Range<T>,
+) -> Explanation {
+    let values = values.join(", ");
+    match truth {
+        Truth::True => Explanation::new(true, format!("{kind} {values} is in {range}")),
+        Truth::False => Explanation::new(false, format!("{kind} {values} is not in {range}")),
+        Truth::Unknown => Explanation::new(false, format!("no {kind} value")),
+    }
+}
+
+/// Records that the data provided a value, checking it unless a previous
+/// value already matched.
+#[inline]
+fn record(truth: &mut Truth, matches: impl FnOnce() -> bool) {
+    if !truth.is_true() {
+        *truth = matches().into();
+    }
+}
+
+#[inline]
+fn text_eval<D: Data + ?Sized>(f: &dyn Filter<str>, d: &D) -> Truth {
+    struct Matcher<'a>(Truth, &'a dyn Filter<str>);
+    impl datalink::value::ValueBuiler<'_> for Matcher<'_> {
+        #[inline]
+        fn str(&mut self, value: std::borrow::Cow<'_, str>) {
+            let f = self.1;
+            record(&mut self.0, || f.matches(value.as_ref()));
+        }
+    }
+    let mut m = Matcher(Truth::Unknown, f);
+    d.borrow().provide_value(&mut m);
+    m.0
+}
+
+#[inline]
+fn number_eval<D: Data + ?Sized>(f: &Range<f64>, d: &D) -> Truth {
+    struct Matcher<'a>(Truth, &'a Range<f64>);
+    macro_rules! number {
+        ($($ty:ident),*) => {$(
+            #[inline]
+            fn $ty(&mut self, value: $ty) {
+                let f = self.1;
+                record(&mut self.0, || f.matches_owned(value as f64));
+            }
+        )*};
+    }
+    impl datalink::value::ValueBuiler<'_> for Matcher<'_> {
+        number!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+    }
+    let mut m = Matcher(Truth::Unknown, f);
+    d.borrow().provide_value(&mut m);
+    m.0
+}
+
+#[inline]
+fn int_eval<D: Data + ?Sized>(f: &Range<i128>, d: &D) -> Truth {
+    struct Matcher<'a>(Truth, &'a Range<i128>);
+    macro_rules! int {
+        ($($ty:ident),*) => {$(
+            #[inline]
+            fn $ty(&mut self, value: $ty) {
+                let f = self.1;
+                record(&mut self.0, || f.matches_owned(value.into()));
+            }
+        )*};
+    }
+    impl datalink::value::ValueBuiler<'_> for Matcher<'_> {
+        int!(u8, i8, u16, i16, u32, i32, u64, i64, i128);
+        #[inline]
+        fn u128(&mut self, value: u128) {
+            let f = self.1;
+            record(&mut self.0, || match i128::try_from(value) {
+                Ok(value) => f.matches_owned(value),
+                // larger than any bound but the missing one
+                Err(_) => {
+                    use std::ops::{Bound, RangeBounds};
+                    matches!(f.end_bound(), Bound::Unbounded)
                 }
-                let mut m = Matcher::Selecting(f);
-                d.borrow().provide_value(&mut m);
-                matches!(m, Matcher::Found)
+            });
+        }
+    }
+    let mut m = Matcher(Truth::Unknown, f);
+    d.borrow().provide_value(&mut m);
+    m.0
+}
+
+#[inline]
+fn float_eval<D: Data + ?Sized>(f: &Range<f64>, d: &D) -> Truth {
+    struct Matcher<'a>(Truth, &'a Range<f64>);
+    impl datalink::value::ValueBuiler<'_> for Matcher<'_> {
+        #[inline]
+        fn f32(&mut self, value: f32) {
+            let f = self.1;
+            record(&mut self.0, || f.matches_owned(value.into()));
+        }
+        #[inline]
+        fn f64(&mut self, value: f64) {
+            let f = self.1;
+            record(&mut self.0, || f.matches_owned(value));
+        }
+    }
+    let mut m = Matcher(Truth::Unknown, f);
+    d.borrow().provide_value(&mut m);
+    m.0
+}
+
+#[inline]
+fn bool_eval<D: Data + ?Sized>(b: bool, d: &D) -> Truth {
+    struct Matcher(Truth, bool);
+    impl datalink::value::ValueBuiler<'_> for Matcher {
+        #[inline]
+        fn bool(&mut self, value: bool) {
+            let b = self.1;
+            record(&mut self.0, || b == value);
+        }
+    }
+    let mut m = Matcher(Truth::Unknown, b);
+    d.borrow().provide_value(&mut m);
+    m.0
+}
+
+/// Operands of an `And` or `Or` without duplicates, looked up by hash.
+struct Operands {
+    hasher: RandomState,
+    buckets: HashMap<u64, Vec<usize>>,
+    operands: Vec<DataFilter>,
+}
+
+impl Operands {
+    /// Collects `operands`, keeping the first of equal ones.
+    fn new(operands: Vec<DataFilter>) -> Self {
+        let mut unique = Self {
+            hasher: RandomState::new(),
+            buckets: HashMap::with_capacity(operands.len()),
+            operands: Vec::with_capacity(operands.len()),
+        };
+        for f in operands {
+            let bucket = unique
+                .buckets
+                .entry(unique.hasher.hash_one(&f))
+                .or_default();
+            if !bucket.iter().any(|&i| unique.operands[i] == f) {
+                bucket.push(unique.operands.len());
+                unique.operands.push(f);
             }
         }
+        unique
+    }
+
+    fn contains(&self, f: &DataFilter) -> bool {
+        self.buckets
+            .get(&self.hasher.hash_one(f))
+            .is_some_and(|bucket| bucket.iter().any(|&i| &self.operands[i] == f))
+    }
+
+    /// Whether an operand is the negation of another.
+    fn complementary(&self) -> bool {
+        let not_ids: HashSet<&ID> = self
+            .operands
+            .iter()
+            .filter_map(|f| match f {
+                DataFilter::NotId(id) => Some(id),
+                _ => None,
+            })
+            .collect();
+        self.operands.iter().any(|f| match f {
+            DataFilter::Not(not) => self.contains(&not.0),
+            DataFilter::Id(id) => not_ids.contains(id),
+            _ => false,
+        })
+    }
+
+    fn count(&self, pred: impl Fn(&DataFilter) -> bool) -> usize {
+        self.operands.iter().filter(|f| pred(f)).count()
+    }
+}
+
+/// Removes duplicate and subsumed operands of a conjunction, returning
+/// `false` if they contradict each other.
+fn simplify_and(and: &mut And<DataFilter>) -> bool {
+    let mut operands = Operands::new(std::mem::take(&mut **and));
+    if operands.complementary() {
+        return false;
+    }
+    match operands.count(|f| matches!(f, DataFilter::Id(_))) {
+        0 => {}
+        // any other ID is excluded already, as is having no ID
+        1 => operands
+            .operands
+            .retain(|f| !matches!(f, DataFilter::NotId(_) | DataFilter::Unique)),
+        _ => return false,
+    }
+    **and = operands.operands;
+    true
+}
+
+/// Removes duplicate and subsumed operands of a disjunction, returning
+/// `true` if one of them always matches.
+fn simplify_or(or: &mut Or<DataFilter>) -> bool {
+    let mut operands = Operands::new(std::mem::take(&mut **or));
+    if operands.complementary() {
+        return true;
+    }
+    match operands.count(|f| matches!(f, DataFilter::NotId(_))) {
+        0 => {}
+        // any other ID is included already
+        1 => operands
+            .operands
+            .retain(|f| !matches!(f, DataFilter::Id(_))),
+        _ => return true,
+    }
+    **or = operands.operands;
+    false
+}
+
+/// Merges the text filters of a disjunction into a single [`TextSet`].
+fn collapse_texts(or: &mut Or<DataFilter>) {
+    let texts = or
+        .iter()
+        .filter(|f| matches!(f, DataFilter::Text(_) | DataFilter::TextSet(_)))
+        .count();
+    if texts < 2 {
+        return;
+    }
+
+    let mut set = Vec::new();
+    let mut rest = Vec::new();
+    for f in std::mem::take(&mut **or) {
+        match f {
+            DataFilter::Text(f) => set.push(f),
+            DataFilter::TextSet(f) => set.extend(f),
+            f => rest.push(f),
+        }
+    }
+    rest.push(DataFilter::TextSet(set.into_iter().collect()));
+    **or = rest;
+}
+
+impl std::fmt::Display for DataFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DataFilter as E;
+        match self {
+            E::Any => f.write_str("any"),
+            E::None => f.write_str("none"),
+            E::And(and) => and.fmt(f),
+            E::Or(or) => or.fmt(f),
+            E::Not(not) => not.fmt(f),
+            E::Id(id) => write!(f, "id({id})"),
+            E::NotId(id) => write!(f, "not_id({id})"),
+            E::Unique => f.write_str("unique"),
+            E::Linked(l) => write!(f, "linked({l})"),
+            E::Text(t) => write!(f, "text({t})"),
+            E::TextSet(t) => write!(f, "text({t})"),
+            E::Number(r) => write!(f, "number({r})"),
+            E::Int(r) => write!(f, "int({r})"),
+            E::Float(r) => write!(f, "float({r})"),
+            E::Bool(b) => write!(f, "bool({b})"),
+        }
+    }
+}
+
+impl Infix for DataFilter {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        match self {
+            DataFilter::And(and) => and.precedence(),
+            DataFilter::Or(or) => or.precedence(),
+            DataFilter::Not(not) => not.precedence(),
+            _ => Precedence::Atom,
+        }
+    }
+}
+
+/// The number of links [`DataFilter::Linked`] is assumed to scan.
+const EXPECTED_LINKS: i32 = 8;
+
+impl Cost for DataFilter {
+    fn cost(&self) -> f64 {
+        use DataFilter as E;
+        match self {
+            E::Any | E::None => 0.0,
+            E::And(f) => f.cost(),
+            E::Or(f) => f.cost(),
+            E::Not(f) => f.cost(),
+            E::Id(_) | E::NotId(_) | E::Unique => 1.0,
+            // getting the value costs about as much as comparing it
+            E::Text(f) => 1.0 + f.cost(),
+            E::TextSet(f) => 1.0 + f.cost(),
+            E::Number(f) | E::Float(f) => 1.0 + f.cost(),
+            E::Int(f) => 1.0 + f.cost(),
+            E::Bool(_) => 2.0,
+            E::Linked(f) => f64::from(EXPECTED_LINKS) * (1.0 + f.cost()),
+        }
+    }
+
+    fn selectivity(&self) -> f64 {
+        use DataFilter as E;
+        match self {
+            E::Any => 1.0,
+            E::None => 0.0,
+            E::And(f) => f.selectivity(),
+            E::Or(f) => f.selectivity(),
+            E::Not(f) => f.selectivity(),
+            E::Id(_) => 0.01,
+            E::NotId(_) => 0.99,
+            E::Text(f) => f.selectivity(),
+            E::TextSet(f) => f.selectivity(),
+            E::Number(f) | E::Float(f) => f.selectivity(),
+            E::Int(f) => f.selectivity(),
+            E::Unique | E::Bool(_) => 0.5,
+            E::Linked(f) => 1.0 - (1.0 - f.selectivity()).powi(EXPECTED_LINKS),
+        }
+    }
+
+    fn reorder(&mut self) {
+        use DataFilter as E;
+        match self {
+            E::And(f) => f.reorder(),
+            E::Or(f) => f.reorder(),
+            E::Not(f) => f.reorder(),
+            E::Linked(f) => f.reorder(),
+            _ => {}
+        }
     }
 }
 
@@ -180,7 +980,11 @@ impl Optimizable for DataFilter {
             E::And(f) => f.as_bool(),
             E::Or(f) => f.as_bool(),
             E::Not(f) => f.as_bool(),
-            E::Text(f) => f.as_bool(),
+            // matching all values still requires the data to have one
+            E::Text(f) => f.as_bool().filter(|b| !b),
+            E::TextSet(f) => f.as_bool().filter(|b| !b),
+            E::Number(f) | E::Float(f) => f.as_bool().filter(|b| !b),
+            E::Int(f) => f.as_bool().filter(|b| !b),
             _ => None,
         }
     }
@@ -189,10 +993,30 @@ impl Optimizable for DataFilter {
     fn optimize(&mut self) {
         use DataFilter as E;
         match self {
-            E::And(f) => f.optimize(),
-            E::Or(f) => f.optimize(),
+            E::And(f) => {
+                f.optimize();
+                if !simplify_and(f) {
+                    *self = Self::None;
+                } else if f.len() == 1 {
+                    *self = f.remove(0);
+                }
+            }
+            E::Or(f) => {
+                f.optimize();
+                if simplify_or(f) {
+                    *self = Self::Any;
+                } else {
+                    collapse_texts(f);
+                    if f.len() == 1 {
+                        *self = f.remove(0);
+                    }
+                }
+            }
             E::Not(f) => f.optimize(),
             E::Text(f) => f.optimize(),
+            E::TextSet(f) => f.optimize(),
+            E::Number(f) | E::Float(f) => f.optimize(),
+            E::Int(f) => f.optimize(),
             _ => {}
         }
         match self.as_bool() {
@@ -238,3 +1062,664 @@ impl std::ops::Not for DataFilter {
         Self::Not(Box::new(Not(self)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::Outcome;
+    use super::*;
+    use datalink::links::{LinkError, Links};
+    use datalink::value::ValueBuiler;
+
+    #[derive(Default)]
+    struct Item {
+        id: Option<ID>,
+        text: Option<&'static str>,
+        number: Option<i64>,
+        float: Option<f64>,
+        flag: Option<bool>,
+        links: Vec<(&'static str, &'static str)>,
+    }
+
+    impl Item {
+        fn text(text: &'static str) -> Self {
+            Self {
+                text: Some(text),
+                ..Default::default()
+            }
+        }
+
+        fn with_id(id: u128) -> Self {
+            Self {
+                id: Some(ID::from(id)),
+                ..Default::default()
+            }
+        }
+
+        fn int(number: i64) -> Self {
+            Self {
+                number: Some(number),
+                ..Default::default()
+            }
+        }
+
+        fn float(float: f64) -> Self {
+            Self {
+                float: Some(float),
+                ..Default::default()
+            }
+        }
+
+        fn flag(flag: bool) -> Self {
+            Self {
+                flag: Some(flag),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Data for Item {
+        fn provide_value<'d>(&'d self, value: &mut dyn ValueBuiler<'d>) {
+            if let Some(text) = self.text {
+                value.str(text.into());
+            }
+            if let Some(number) = self.number {
+                value.i64(number);
+            }
+            if let Some(float) = self.float {
+                value.f64(float);
+            }
+            if let Some(flag) = self.flag {
+                value.bool(flag);
+            }
+        }
+
+        fn provide_links(&self, links: &mut dyn Links) -> Result<(), LinkError> {
+            for &(key, target) in &self.links {
+                let key = Box::new(Item::text(key));
+                let target = Box::new(Item::text(target));
+                if links.push_keyed(target, key)?.is_break() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+
+        fn get_id(&self) -> Option<ID> {
+            self.id
+        }
+    }
+
+    #[test]
+    fn id() {
+        let f = DataFilter::id(ID::from(1));
+
+        assert!(f.matches(&Item::with_id(1)));
+        assert!(!f.matches(&Item::with_id(2)));
+        assert!(!f.matches(&Item::default()));
+    }
+
+    #[test]
+    fn not_id() {
+        let f = DataFilter::not_id(ID::from(1));
+
+        assert!(!f.matches(&Item::with_id(1)));
+        assert!(f.matches(&Item::with_id(2)));
+        assert!(f.matches(&Item::default()));
+    }
+
+    #[test]
+    fn unique() {
+        let f = DataFilter::unique();
+
+        assert!(f.matches(&Item::with_id(1)));
+        assert!(!f.matches(&Item::default()));
+    }
+
+    #[test]
+    fn text() {
+        let f = DataFilter::text("foo");
+
+        assert!(f.matches(&Item::text("foo")));
+        assert!(!f.matches(&Item::text("bar")));
+        assert!(!f.matches(&Item::default()));
+
+        let mut f = DataFilter::text(TextFilter::contains(""));
+        f.optimize();
+        assert!(f.matches(&Item::text("foo")));
+        assert!(!f.matches(&Item::default()));
+    }
+
+    #[test]
+    fn number() {
+        let f = DataFilter::number(1.0..=3.0);
+
+        assert!(f.matches(&Item::int(1)));
+        assert!(f.matches(&Item::int(3)));
+        assert!(!f.matches(&Item::int(4)));
+        assert!(f.matches(&Item::float(1.5)));
+        assert!(!f.matches(&Item::text("2")));
+
+        let mut f = DataFilter::number(..);
+        f.optimize();
+        assert!(matches!(f, DataFilter::Number(_)));
+        assert!(!f.matches(&Item::default()));
+
+        let mut f = DataFilter::number(3.0..1.0);
+        f.optimize();
+        assert!(matches!(f, DataFilter::None));
+    }
+
+    #[test]
+    fn int() {
+        let f = DataFilter::int(..10);
+
+        assert!(f.matches(&Item::int(-3)));
+        assert!(!f.matches(&Item::int(10)));
+        assert!(!f.matches(&Item::float(1.0)));
+    }
+
+    #[test]
+    fn float() {
+        let f = DataFilter::float(Range::gt(0.5));
+
+        assert!(f.matches(&Item::float(0.75)));
+        assert!(!f.matches(&Item::float(0.5)));
+        assert!(!f.matches(&Item::float(f64::NAN)));
+        // unlike `Number`, `Float` ignores integers
+        assert!(!f.matches(&Item::int(1)));
+        assert!(DataFilter::number(Range::gt(0.5)).matches(&Item::int(1)));
+        assert!(!DataFilter::float(..).equivalent(&DataFilter::number(..)));
+    }
+
+    #[test]
+    fn bool() {
+        assert!(DataFilter::bool(true).matches(&Item::flag(true)));
+        assert!(!DataFilter::bool(true).matches(&Item::flag(false)));
+        assert!(DataFilter::bool(false).matches(&Item::flag(false)));
+        assert!(!DataFilter::bool(false).matches(&Item::default()));
+    }
+
+    #[test]
+    fn linked() {
+        let item = Item {
+            links: vec![("name", "foo"), ("kind", "bar")],
+            ..Default::default()
+        };
+
+        let f = DataFilter::linked(LinkFilter::key(DataFilter::text("name")));
+        assert!(f.matches(&item));
+
+        let f = DataFilter::linked(
+            LinkFilter::key(DataFilter::text("kind")) & LinkFilter::target(DataFilter::text("bar")),
+        );
+        assert!(f.matches(&item));
+
+        let f = DataFilter::linked(
+            LinkFilter::key(DataFilter::text("name")) & LinkFilter::target(DataFilter::text("bar")),
+        );
+        assert!(!f.matches(&item));
+        assert!(!f.matches(&Item::default()));
+
+        let item = Item {
+            links: vec![("name", "foo")],
+            ..Default::default()
+        };
+        let f = DataFilter::linked(!LinkFilter::key(DataFilter::text("name")));
+        assert!(!f.matches(&item));
+    }
+
+    #[test]
+    fn text_set() {
+        let mut f = DataFilter::text("foo")
+            | DataFilter::unique()
+            | DataFilter::text(TextFilter::contains("bar"));
+        f.optimize();
+
+        let DataFilter::Or(or) = &f else {
+            panic!("expected a disjunction, got {f:?}");
+        };
+        assert_eq!(or.len(), 2);
+        assert!(matches!(or[1], DataFilter::TextSet(ref set) if set.len() == 2));
+
+        assert!(f.matches(&Item::text("foo")));
+        assert!(f.matches(&Item::text("xbarx")));
+        assert!(f.matches(&Item::with_id(1)));
+        assert!(!f.matches(&Item::text("baz")));
+    }
+
+    #[test]
+    fn contradictions() {
+        let optimized = |mut f: DataFilter| {
+            f.optimize();
+            f
+        };
+        let id = |i: u128| DataFilter::id(ID::from(i));
+        let not_id = |i: u128| DataFilter::not_id(ID::from(i));
+        let x = DataFilter::text("x");
+        let y = DataFilter::int(..3);
+
+        assert_eq!(optimized(id(1) & id(1)), id(1));
+        assert_eq!(optimized(id(1) & id(2)), DataFilter::None);
+        assert_eq!(optimized(id(1) & not_id(1)), DataFilter::None);
+        assert_eq!(optimized(x.clone() & !x.clone()), DataFilter::None);
+        assert_eq!(
+            optimized(DataFilter::Any & DataFilter::None),
+            DataFilter::None
+        );
+        assert_eq!(optimized(id(1) & not_id(2) & DataFilter::unique()), id(1));
+
+        assert_eq!(optimized(y.clone() | !y.clone()), DataFilter::Any);
+        assert_eq!(optimized(not_id(1) | id(1)), DataFilter::Any);
+        assert_eq!(optimized(not_id(1) | not_id(2)), DataFilter::Any);
+        assert_eq!(optimized(not_id(1) | id(2)), not_id(1));
+        assert_eq!(
+            optimized(y.clone() | y.clone() | x.clone()).to_string(),
+            r#"int(..3) | text("x")"#
+        );
+
+        // contradictions deep inside a generated query collapse it entirely
+        let clause = |i: i128| DataFilter::int(i..) | (x.clone() & !x.clone());
+        let f = (0..50).map(clause).fold(id(1), DataFilter::and) & id(2);
+        assert_eq!(optimized(f), DataFilter::None);
+        let f = (0..50).map(|_| y.clone()).fold(x.clone(), DataFilter::or);
+        assert_eq!(optimized(f).to_string(), r#"text("x") | int(..3)"#);
+    }
+
+    #[test]
+    fn tri_state() {
+        let unknown = Item::default();
+
+        assert_eq!(DataFilter::id(ID::from(1)).eval(&unknown), Truth::False);
+        assert_eq!(DataFilter::not_id(ID::from(1)).eval(&unknown), Truth::True);
+        assert_eq!(
+            DataFilter::not_id(ID::from(1)).eval(&Item::with_id(2)),
+            Truth::True
+        );
+        assert_eq!(DataFilter::unique().eval(&unknown), Truth::False);
+        assert_eq!(DataFilter::text("foo").eval(&unknown), Truth::Unknown);
+        assert_eq!(
+            DataFilter::text("foo").eval(&Item::text("bar")),
+            Truth::False
+        );
+        assert_eq!(DataFilter::int(..).eval(&Item::text("bar")), Truth::Unknown);
+        assert_eq!(DataFilter::bool(true).eval(&unknown), Truth::Unknown);
+
+        let f = DataFilter::text("foo") & DataFilter::unique();
+        assert_eq!(f.eval(&unknown), Truth::False);
+        let f = DataFilter::text("foo") | DataFilter::unique();
+        assert_eq!(f.eval(&unknown), Truth::Unknown);
+        assert_eq!((!f).eval(&unknown), Truth::Unknown);
+
+        let f = DataFilter::linked(LinkFilter::key(DataFilter::text("name")));
+        assert_eq!(f.eval(&unknown), Truth::Unknown);
+        let item = Item {
+            links: vec![("kind", "foo")],
+            ..Default::default()
+        };
+        assert_eq!(f.eval(&item), Truth::False);
+        let f = DataFilter::linked(LinkFilter::target(DataFilter::int(..)));
+        assert_eq!(f.eval(&item), Truth::Unknown);
+    }
+
+    #[test]
+    fn tri_state_agrees() {
+        let items = [
+            Item::default(),
+            Item::with_id(1),
+            Item::with_id(2),
+            Item::text("a"),
+            Item::int(2),
+            Item::flag(true),
+            Item {
+                links: vec![("name", "a")],
+                ..Default::default()
+            },
+        ];
+        let id = DataFilter::id(ID::from(1));
+        let a = DataFilter::text("a");
+        let linked = DataFilter::linked(LinkFilter::key(DataFilter::text("name")));
+        let filters = [
+            id.clone(),
+            DataFilter::not_id(ID::from(1)),
+            !id.clone(),
+            DataFilter::unique(),
+            a.clone(),
+            !a.clone(),
+            DataFilter::int(..3),
+            DataFilter::bool(true),
+            linked.clone(),
+            !linked.clone(),
+            a.clone() | !id.clone(),
+            !(a & DataFilter::int(..)) | linked,
+        ];
+        for f in &filters {
+            for item in &items {
+                if let Some(known) = f.eval(item).known() {
+                    assert_eq!(known, f.matches(item), "{f}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn explain() {
+        let item = Item {
+            id: Some(ID::from(1)),
+            text: Some("bar"),
+            number: Some(3),
+            links: vec![("kind", "foo")],
+            ..Default::default()
+        };
+
+        let e = DataFilter::text("foo").explain(&item);
+        assert!(!e.matched());
+        assert_eq!(e.detail, "text \"bar\" does not equal \"foo\"");
+
+        let e = DataFilter::int(5..).explain(&item);
+        assert_eq!(e.detail, "int 3 is not in 5..");
+        assert_eq!(
+            DataFilter::bool(true).explain(&item).detail,
+            "no bool value"
+        );
+
+        let f = DataFilter::linked(LinkFilter::key(DataFilter::text("name")));
+        let e = f.explain(&item);
+        assert!(!e.matched());
+        assert_eq!(e.children[0].detail, "link key did not match");
+        assert_eq!(
+            e.children[0].children[0].detail,
+            "text \"kind\" does not equal \"name\""
+        );
+
+        let f = DataFilter::text("foo") & DataFilter::unique() & DataFilter::int(..5);
+        let e = f.explain(&item);
+        assert_eq!(e.matched(), f.matches(&item));
+        assert_eq!(
+            e.children.iter().map(|e| e.outcome).collect::<Vec<_>>(),
+            [Outcome::Failed, Outcome::Skipped, Outcome::Skipped]
+        );
+        assert_eq!(e.children[2].detail, "int in ..5");
+
+        let f = !DataFilter::id(ID::from(2));
+        assert!(f.explain(&item).matched());
+
+        let f = DataFilter::linked(!LinkFilter::key(DataFilter::text("kind")));
+        assert!(!f.matches(&item));
+        assert!(!f.explain(&item).matched());
+    }
+
+    #[test]
+    fn display() {
+        let f = DataFilter::text("foo")
+            & !(DataFilter::id(ID::from(1))
+                | DataFilter::linked(LinkFilter::key(DataFilter::text("name"))));
+        assert_eq!(
+            f.to_string(),
+            format!(
+                r#"text("foo") & !(id({}) | linked(key(text("name"))))"#,
+                ID::from(1)
+            )
+        );
+
+        let f = (DataFilter::int(1..) | DataFilter::bool(true)) & DataFilter::float(..=0.5);
+        assert_eq!(f.to_string(), "(int(1..) | bool(true)) & float(..=0.5)");
+
+        let f = !DataFilter::unique() | (DataFilter::any() & DataFilter::none());
+        assert_eq!(f.to_string(), "!unique | any & none");
+
+        let f: TextSet = ["b", "a"].into_iter().map(TextFilter::from).collect();
+        assert_eq!(DataFilter::TextSet(f).to_string(), r#"text(set("a", "b"))"#);
+
+        let [a, b, c] = ["a", "b", "c"].map(DataFilter::text);
+        let f = DataFilter::And(
+            vec![
+                a.clone(),
+                DataFilter::And(vec![b.clone(), c.clone()].into()),
+            ]
+            .into(),
+        );
+        assert_eq!(f.to_string(), r#"text("a") & (text("b") & text("c"))"#);
+        let f = DataFilter::Or(vec![DataFilter::Or(vec![a.clone(), b].into()), c].into());
+        assert_eq!(f.to_string(), r#"(text("a") | text("b")) | text("c")"#);
+        let f = DataFilter::And(vec![a.clone()].into());
+        assert_eq!(f.to_string(), r#"and(text("a"))"#);
+        assert_eq!(f.precedence(), Precedence::Atom);
+        let f = !DataFilter::Or(vec![a].into());
+        assert_eq!(f.to_string(), r#"!or(text("a"))"#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        #[track_caller]
+        fn round_trip(f: &DataFilter, json: &str) {
+            assert_eq!(serde_json::to_string(f).unwrap(), json);
+            let parsed: DataFilter = serde_json::from_str(json).unwrap();
+            assert_eq!(&parsed, f);
+        }
+
+        let id = ID::from(0xab);
+        round_trip(&DataFilter::Any, r#""any""#);
+        round_trip(&DataFilter::None, r#""none""#);
+        round_trip(&DataFilter::Unique, r#""unique""#);
+        round_trip(
+            &(DataFilter::text("foo") & !DataFilter::id(id)),
+            &format!(r#"{{"and":[{{"text":"foo"}},{{"not":{{"id":"{id}"}}}}]}}"#),
+        );
+        round_trip(
+            &(DataFilter::bool(true) | DataFilter::not_id(id)),
+            &format!(r#"{{"or":[{{"bool":true}},{{"not_id":"{id}"}}]}}"#),
+        );
+        round_trip(
+            &DataFilter::text(TextFilter::contains("a")),
+            r#"{"text":{"contains":"a"}}"#,
+        );
+        round_trip(
+            &DataFilter::TextSet(["a", "b"].into_iter().map(TextFilter::from).collect()),
+            r#"{"text_set":["a","b"]}"#,
+        );
+        round_trip(&DataFilter::number(1.5..), r#"{"number":{"ge":1.5}}"#);
+        round_trip(&DataFilter::int(..=3), r#"{"int":{"le":3}}"#);
+        round_trip(&DataFilter::float(..), r#"{"float":{}}"#);
+        assert!(serde_json::to_string(&DataFilter::number(..f64::INFINITY)).is_err());
+        assert!(serde_json::to_string(&DataFilter::float(f64::NEG_INFINITY..)).is_err());
+        assert!(serde_json::to_string(&DataFilter::number(f64::NAN..=0.0)).is_err());
+        round_trip(
+            &DataFilter::linked(
+                LinkFilter::key(DataFilter::text("name")) | !LinkFilter::target(DataFilter::Any),
+            ),
+            r#"{"linked":{"or":[{"key":{"text":"name"}},{"not":{"target":"any"}}]}}"#,
+        );
+        round_trip(
+            &DataFilter::linked(LinkFilter::Any & LinkFilter::None),
+            r#"{"linked":{"and":["any","none"]}}"#,
+        );
+
+        let e = serde_json::from_str::<DataFilter>(r#"{"fuzzy":"foo"}"#).unwrap_err();
+        assert!(e.to_string().starts_with("unknown variant `fuzzy`"));
+        let e = serde_json::from_str::<DataFilter>(r#""everything""#).unwrap_err();
+        assert!(e.to_string().starts_with("unknown variant `everything`"));
+        let e = serde_json::from_str::<LinkFilter>(r#"{"source":"any"}"#).unwrap_err();
+        assert!(e.to_string().starts_with("unknown variant `source`"));
+        assert!(serde_json::from_str::<DataFilter>(r#"{"id":"xyz"}"#).is_err());
+    }
+
+    #[test]
+    fn eq() {
+        use std::collections::hash_map::RandomState;
+        use std::collections::HashSet;
+        use std::hash::BuildHasher;
+
+        let f = DataFilter::text("foo") & !DataFilter::float(..f64::NAN);
+        assert_eq!(f, f.clone());
+        assert_ne!(f, DataFilter::text("foo") & !DataFilter::number(..f64::NAN));
+        assert_ne!(
+            DataFilter::text("a") & DataFilter::text("b"),
+            DataFilter::text("b") & DataFilter::text("a")
+        );
+
+        // hashes rather than filters as keys, as a regex filter's match cache
+        // makes `DataFilter` a mutable key type
+        let state = RandomState::new();
+        let set: HashSet<u64> = [
+            DataFilter::id(ID::from(1)),
+            DataFilter::id(ID::from(1)),
+            DataFilter::not_id(ID::from(1)),
+            DataFilter::linked(LinkFilter::key(DataFilter::Unique)),
+            DataFilter::linked(LinkFilter::key(DataFilter::Unique)),
+        ]
+        .iter()
+        .map(|f| state.hash_one(f))
+        .collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn equivalent() {
+        let a = DataFilter::text("a");
+        let b = DataFilter::int(1..);
+        let c = DataFilter::Unique;
+
+        assert!((a.clone() & b.clone()).equivalent(&(b.clone() & a.clone())));
+        assert!(
+            (a.clone() & (b.clone() & c.clone())).equivalent(&(c.clone() & a.clone() & b.clone()))
+        );
+        assert!((a.clone() | a.clone()).equivalent(&a));
+        assert!((!!a.clone()).equivalent(&a));
+        assert!((a.clone() & DataFilter::Any).equivalent(&a));
+        assert!(!(a.clone() & b.clone()).equivalent(&(a.clone() | b.clone())));
+        assert!(!(a.clone() & b.clone()).equivalent(&(a.clone() & !b.clone())));
+
+        let f = DataFilter::linked(LinkFilter::key(a.clone()) & LinkFilter::target(b.clone()));
+        let g = DataFilter::linked(LinkFilter::target(b) & LinkFilter::key(!!a));
+        assert!(f.equivalent(&g));
+        assert_ne!(f, g);
+    }
+
+    #[test]
+    fn normalize() {
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(DataFilter::text);
+
+        let f = DataFilter::And(
+            vec![
+                a.clone(),
+                DataFilter::And(vec![b.clone(), !!c.clone()].into()),
+            ]
+            .into(),
+        );
+        assert_eq!(
+            f.flatten().to_string(),
+            r#"text("a") & text("b") & text("c")"#
+        );
+
+        let f = !(a.clone() & (b.clone() | !c.clone()));
+        assert_eq!(
+            f.to_nnf().to_string(),
+            r#"!text("a") | !text("b") & text("c")"#
+        );
+        assert_eq!((!DataFilter::Any).to_nnf(), DataFilter::None);
+        assert_eq!(
+            (!DataFilter::id(ID::from(1))).to_nnf(),
+            DataFilter::not_id(ID::from(1))
+        );
+
+        let f = (a.clone() | b.clone()) & (c.clone() | d.clone());
+        assert_eq!(
+            f.clone().to_dnf(4).unwrap().to_string(),
+            r#"text("a") & text("c") | text("a") & text("d") | text("b") & text("c") | text("b") & text("d")"#
+        );
+        assert_eq!(f.clone().to_cnf(2).unwrap(), f.clone().flatten());
+        assert_eq!(f.to_dnf(3).unwrap_err().limit(), 3);
+
+        let f = DataFilter::linked(!(LinkFilter::key(!!a) | LinkFilter::Any));
+        assert_eq!(f.to_nnf().to_string(), r#"linked(!key(text("a")) & none)"#);
+    }
+
+    #[test]
+    fn cost() {
+        let linked =
+            DataFilter::linked(LinkFilter::key(DataFilter::text(TextFilter::contains("a"))));
+        let id = DataFilter::id(ID::from(1));
+        let mut f = linked.clone() & DataFilter::text("b") & DataFilter::Any & id.clone();
+        assert!(f.cost() > linked.cost());
+
+        f.optimize_by_cost();
+        assert_eq!(f, id & DataFilter::text("b") & linked);
+        assert!(f.cost() < 2.0);
+
+        // a likely match decides a disjunction early
+        let not_id = DataFilter::not_id(ID::from(1));
+        let mut f = DataFilter::text("b") | not_id.clone();
+        f.reorder();
+        assert_eq!(f, not_id | DataFilter::text("b"));
+    }
+
+    #[test]
+    fn compile() {
+        let items = [
+            Item::default(),
+            Item::with_id(1),
+            Item::with_id(2),
+            Item::text("a"),
+            Item::text("b"),
+            Item {
+                id: Some(ID::from(1)),
+                text: Some("a"),
+                number: Some(5),
+                links: vec![("name", "a")],
+                ..Default::default()
+            },
+        ];
+        let [a, b] = ["a", "b"].map(DataFilter::text);
+        let id = DataFilter::id(ID::from(1));
+        let linked = DataFilter::linked(LinkFilter::target(a.clone()));
+        let filters = [
+            DataFilter::Any,
+            DataFilter::None,
+            DataFilter::And(Vec::new().into()),
+            DataFilter::Or(Vec::new().into()),
+            a.clone() & id.clone(),
+            a.clone() | !id.clone(),
+            !(a.clone() & (b.clone() | id.clone())),
+            !!(linked.clone() | DataFilter::int(..3)) & !b.clone(),
+            (a.clone() | b.clone()) & (id.clone() | !linked.clone()) & DataFilter::unique(),
+            !((a.clone() & !b.clone()) | (!id.clone() & linked)) | DataFilter::None,
+        ];
+        for f in &filters {
+            let compiled = f.compile();
+            assert_eq!(compiled.cost(), f.cost());
+            for item in &items {
+                assert_eq!(
+                    compiled.matches(item),
+                    f.matches(item),
+                    "{f} on\n{compiled}"
+                );
+            }
+        }
+
+        let test_id = format!("4: test_not {id}");
+        let f = (a.clone() & b.clone()) | !(id & a);
+        assert_eq!(
+            f.compile().to_string(),
+            [
+                "0: test text(\"a\")",
+                "1: jump_if false 4",
+                "2: test text(\"b\")",
+                "3: jump_if true 7",
+                &test_id,
+                "5: jump_if true 7",
+                "6: test_not text(\"a\")",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn combined() {
+        let f = DataFilter::unique() & !DataFilter::id(ID::from(1));
+
+        assert!(f.matches(&Item::with_id(2)));
+        assert!(!f.matches(&Item::with_id(1)));
+        assert!(!f.matches(&Item::default()));
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+/// How tightly the outermost operator of a filter binds in infix notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    Or,
+    And,
+    Not,
+    Atom,
+}
+
+/// A filter that can be displayed as an operand of `&`, `|` and `!`.
+pub trait Infix: fmt::Display {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        Precedence::Atom
+    }
+}
+
+/// Writes `operand`, parenthesized if it binds looser than `min`.
+#[inline]
+pub(super) fn fmt_operand<F: Infix + ?Sized>(
+    f: &mut fmt::Formatter<'_>,
+    operand: &F,
+    min: Precedence,
+) -> fmt::Result {
+    if operand.precedence() < min {
+        write!(f, "({operand})")
+    } else {
+        write!(f, "{operand}")
+    }
+}
+
+/// Writes `operands` separated by `op`, parenthesizing those that don't bind
+/// tighter than `op`, so a nested operator of the same kind stays distinct.
+#[inline]
+pub(super) fn fmt_operands<'a, F: Infix + 'a>(
+    f: &mut fmt::Formatter<'_>,
+    operands: impl IntoIterator<Item = &'a F>,
+    op: &str,
+    precedence: Precedence,
+) -> fmt::Result {
+    for (i, operand) in operands.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(op)?;
+        }
+        if operand.precedence() <= precedence {
+            write!(f, "({operand})")?;
+        } else {
+            write!(f, "{operand}")?;
+        }
+    }
+    Ok(())
+}
+
+impl Infix for bool {}
+
+impl<F: Infix + ?Sized> Infix for Box<F> {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        self.as_ref().precedence()
+    }
+}
+
+impl<F: Infix + ?Sized> Infix for &F {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        (**self).precedence()
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt::Debug;
+
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// A [`Filter`] that can be optimized, costed and debugged as a trait object.
+pub trait DynFilter<T: ?Sized>: Filter<T> + Optimizable + Cost + Debug {}
+
+impl<F: Filter<T> + Optimizable + Cost + Debug, T: ?Sized> DynFilter<T> for F {}
+
+/// A boxed [`DynFilter`].
+pub type BoxedFilter<'a, T> = Box<dyn DynFilter<T> + 'a>;
+
+/// Optimizes a boxed filter, replacing it with a constant if it turned out
+/// to be unconditional.
+#[inline]
+pub fn optimize_boxed<T: ?Sized>(f: &mut BoxedFilter<'_, T>) {
+    f.as_mut().optimize();
+    if let Some(b) = f.as_ref().as_bool() {
+        *f = Box::new(b);
+    }
+}
+
+impl<T: ?Sized> Optimizable for BoxedFilter<'_, T> {
+    #[inline]
+    fn optimize(&mut self) {
+        optimize_boxed(self);
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
+}
+
+impl<T: ?Sized> TruthyDefault for BoxedFilter<'_, T> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Box::new(true)
+    }
+}
+
+impl<T: ?Sized> FalsyDefault for BoxedFilter<'_, T> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Box::new(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{from_fn, And, Not, Range, TestFilter};
+    use super::*;
+
+    #[test]
+    fn replace_constant() {
+        let mut f: BoxedFilter<'_, ()> = Box::new(Not(TestFilter::None));
+        assert_eq!(f.as_bool(), Some(true));
+
+        optimize_boxed(&mut f);
+        assert_eq!(format!("{f:?}"), "true");
+    }
+
+    #[test]
+    fn fold() {
+        let mut f = And::from(vec![
+            Box::new(Range::gt(0)) as BoxedFilter<'_, i32>,
+            Box::new(Not(Range::<i32>::from(1..1))),
+        ]);
+
+        assert!(f.matches(&1));
+        assert_eq!(f.as_bool(), None);
+
+        f.optimize();
+        assert_eq!(f.len(), 1);
+        assert!(!f.matches(&0));
+
+        f.push(Box::new(Range::from(1..1)));
+        f.optimize();
+        assert_eq!(f.as_bool(), Some(false));
+    }
+
+    #[test]
+    fn object_safety() {
+        #[derive(Debug)]
+        struct Plugin;
+        impl Filter<str> for Plugin {
+            fn matches(&self, obj: &str) -> bool {
+                from_fn(|s: &str| s.is_empty()).matches(obj)
+            }
+        }
+        impl Optimizable for Plugin {}
+        impl Cost for Plugin {
+            fn cost(&self) -> f64 {
+                2.0
+            }
+        }
+
+        let f: BoxedFilter<'_, str> = Box::new(Plugin);
+        assert!(f.matches(""));
+        assert_eq!(f.as_bool(), None);
+        assert_eq!(f.cost(), 2.0);
+    }
+}
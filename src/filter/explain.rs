@@ -0,0 +1,97 @@
+use std::fmt;
+
+use super::Filter;
+
+/// How a node of an [`Explanation`] contributed to the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Matched,
+    Failed,
+    /// Not evaluated, because an earlier sibling already decided the result.
+    Skipped,
+}
+
+/// A tree mirroring a filter, recording why an object matched or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub outcome: Outcome,
+    pub detail: String,
+    pub children: Vec<Explanation>,
+}
+
+impl Explanation {
+    #[inline]
+    #[must_use]
+    pub fn new(matched: bool, detail: impl Into<String>) -> Self {
+        let outcome = if matched {
+            Outcome::Matched
+        } else {
+            Outcome::Failed
+        };
+        Self {
+            outcome,
+            detail: detail.into(),
+            children: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn skipped(detail: impl Into<String>) -> Self {
+        Self {
+            outcome: Outcome::Skipped,
+            detail: detail.into(),
+            children: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_children(mut self, children: Vec<Explanation>) -> Self {
+        self.children = children;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn matched(&self) -> bool {
+        self.outcome == Outcome::Matched
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let outcome = match self.outcome {
+            Outcome::Matched => "matched",
+            Outcome::Failed => "failed",
+            Outcome::Skipped => "skipped",
+        };
+        writeln!(
+            f,
+            "{:indent$}{outcome}: {}",
+            "",
+            self.detail,
+            indent = depth * 2
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Explanation {
+    /// Prints the tree, one indented line per node.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// A filter that can report why an object matched or not.
+///
+/// The outcome of the returned explanation always agrees with
+/// [`Filter::matches`].
+pub trait Explain<T: ?Sized>: Filter<T> {
+    fn explain(&self, obj: &T) -> Explanation;
+
+    /// Describes the filter without evaluating it, used for skipped nodes.
+    fn describe(&self) -> String;
+}
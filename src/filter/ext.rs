@@ -0,0 +1,83 @@
+use super::{AndThen, Filter, Not, OrElse, XorWith};
+
+/// Combinators available on every [`Filter`].
+pub trait FilterExt<T: ?Sized>: Filter<T> + Sized {
+    /// Matches if both `self` and `other` match.
+    #[inline]
+    #[must_use]
+    fn and<F: Filter<T>>(self, other: F) -> AndThen<Self, F> {
+        AndThen(self, other)
+    }
+
+    /// Matches if `self` or `other` matches.
+    #[inline]
+    #[must_use]
+    fn or<F: Filter<T>>(self, other: F) -> OrElse<Self, F> {
+        OrElse(self, other)
+    }
+
+    /// Matches if `self` doesn't match.
+    #[inline]
+    #[must_use]
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+
+    /// Matches if exactly one of `self` and `other` matches.
+    #[inline]
+    #[must_use]
+    fn xor<F: Filter<T>>(self, other: F) -> XorWith<Self, F> {
+        XorWith(self, other)
+    }
+
+    /// Erases the type of the filter.
+    #[inline]
+    #[must_use]
+    fn boxed<'a>(self) -> Box<dyn Filter<T> + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self)
+    }
+}
+
+impl<F: Filter<T>, T: ?Sized> FilterExt<T> for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{from_fn, Range, TextFilter};
+    use super::*;
+
+    #[test]
+    fn combinators() {
+        let f = TextFilter::from("a").or(TextFilter::from("b"));
+        assert!(f.matches("a"));
+        assert!(f.matches("b"));
+        assert!(!f.matches("c"));
+
+        let f = TextFilter::starts_with("a")
+            .and(TextFilter::ends_with("b").not())
+            .or(TextFilter::from("c"));
+        assert!(f.matches("ac"));
+        assert!(!f.matches("ab"));
+        assert!(f.matches("c"));
+
+        let f = Range::lt(5).xor(from_fn(|n: &i32| n % 2 == 0));
+        assert!(f.matches(&3));
+        assert!(f.matches(&6));
+        assert!(!f.matches(&4));
+        assert!(!f.matches(&7));
+    }
+
+    #[test]
+    fn boxed() {
+        let filters = [
+            TextFilter::from("a").boxed(),
+            from_fn(|s: &str| s.len() > 3).boxed(),
+        ];
+
+        assert!(filters.iter().any(|f| f.matches("a")));
+        assert!(filters.iter().any(|f| f.matches("abcd")));
+        assert!(!filters.iter().any(|f| f.matches("b")));
+    }
+}
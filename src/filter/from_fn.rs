@@ -0,0 +1,73 @@
+use super::{Cost, Filter, Optimizable};
+
+/// A filter backed by a closure, created with [`from_fn`].
+///
+/// A blanket impl for closures isn't possible, as it would overlap with the
+/// impl for [`Box`], which implements `Fn` for boxed closures.
+#[derive(Clone, Copy)]
+pub struct FnFilter<F>(pub F);
+
+/// Creates a filter matching all objects for which `f` returns `true`.
+#[inline]
+pub fn from_fn<T: ?Sized, F: Fn(&T) -> bool>(f: F) -> FnFilter<F> {
+    FnFilter(f)
+}
+
+impl<F> std::fmt::Debug for FnFilter<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FnFilter").finish_non_exhaustive()
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T) -> bool> Filter<T> for FnFilter<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        (self.0)(obj)
+    }
+}
+
+impl<F> Optimizable for FnFilter<F> {}
+
+/// Closures are opaque, so they're assumed to cost about as much as a
+/// single comparison.
+impl<F> Cost for FnFilter<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{And, Not, Or, TextFilter};
+    use super::*;
+
+    #[test]
+    fn closure() {
+        let f = from_fn(|s: &str| s.len() > 3);
+
+        assert!(f.matches("foobar"));
+        assert!(!f.matches("foo"));
+        assert!(Not(f).matches("foo"));
+        assert_eq!(f.as_bool(), None);
+    }
+
+    #[test]
+    fn mixed() {
+        let f: And<Box<dyn Filter<str>>> = vec![
+            Box::new(from_fn(|s: &str| s.starts_with('f'))) as Box<dyn Filter<str>>,
+            Box::new(Not(TextFilter::from("foo"))),
+        ]
+        .into();
+
+        assert!(f.matches("foobar"));
+        assert!(!f.matches("foo"));
+        assert!(!f.matches("bar"));
+
+        let negative: fn(&i32) -> bool = |n| *n < 0;
+        let large: fn(&i32) -> bool = |n| *n > 10;
+        let f = Or::from(vec![from_fn(negative), from_fn(large)]);
+        assert!(f.matches(&-1));
+        assert!(!f.matches(&5));
+    }
+}
@@ -0,0 +1,85 @@
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if both filters match or both don't.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Iff<A, B>(pub A, pub B);
+
+impl<A: Filter<T>, B: Filter<T>, T: ?Sized> Filter<T> for Iff<A, B> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.0.matches(obj) == self.1.matches(obj)
+    }
+}
+
+impl<A: Optimizable, B: Optimizable> Optimizable for Iff<A, B> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Some(self.0.as_bool()? == self.1.as_bool()?)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        self.0.optimize();
+        self.1.optimize();
+    }
+}
+
+/// Both filters are evaluated, so their costs add up.
+impl<A: Cost, B: Cost> Cost for Iff<A, B> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.0.cost() + self.1.cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        let (a, b) = (self.0.selectivity(), self.1.selectivity());
+        a * b + (1.0 - a) * (1.0 - b)
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.0.reorder();
+        self.1.reorder();
+    }
+}
+
+impl<A: TruthyDefault, B: TruthyDefault> TruthyDefault for Iff<A, B> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self(A::truthy_default(), B::truthy_default())
+    }
+}
+
+impl<A: TruthyDefault, B: FalsyDefault> FalsyDefault for Iff<A, B> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self(A::truthy_default(), B::falsy_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TestFilter;
+    use super::*;
+
+    #[test]
+    fn matches() {
+        assert!(Iff(TestFilter::None, TestFilter::None).matches(&()));
+        assert!(Iff(TestFilter::Any, TestFilter::Any).matches(&()));
+        assert!(!Iff(TestFilter::Any, TestFilter::None).matches(&()));
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(
+            Iff(TestFilter::None, TestFilter::None).as_bool(),
+            Some(true)
+        );
+        assert_eq!(Iff(TestFilter::Panic, TestFilter::Any).as_bool(), None);
+        assert_eq!(
+            Iff::<TestFilter, TestFilter>::falsy_default().as_bool(),
+            Some(false)
+        );
+    }
+}
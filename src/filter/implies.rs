@@ -0,0 +1,107 @@
+use super::cost;
+use super::{Cost, FalsyDefault, Filter, Not, Optimizable, TruthyDefault};
+
+/// Matches unless `A` matches and `B` doesn't.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Implies<A, B>(pub A, pub B);
+
+impl<A: Filter<T>, B: Filter<T>, T: ?Sized> Filter<T> for Implies<A, B> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        !self.0.matches(obj) || self.1.matches(obj)
+    }
+}
+
+impl<A: Optimizable, B: Optimizable> Optimizable for Implies<A, B> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        match (self.0.as_bool(), self.1.as_bool()) {
+            (Some(false), _) | (_, Some(true)) => Some(true),
+            (Some(true), Some(false)) => Some(false),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        self.0.optimize();
+        self.1.optimize();
+    }
+}
+
+/// `B` is only evaluated if `A` matched, like in `!A || B`.
+impl<A: Cost, B: Cost> Cost for Implies<A, B> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost::sequence([&Not(&self.0) as &dyn Cost, &self.1], true).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost::sequence([&Not(&self.0) as &dyn Cost, &self.1], true).1
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.0.reorder();
+        self.1.reorder();
+    }
+}
+
+impl<A: FalsyDefault, B: TruthyDefault> TruthyDefault for Implies<A, B> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self(A::falsy_default(), B::truthy_default())
+    }
+}
+
+impl<A: TruthyDefault, B: FalsyDefault> FalsyDefault for Implies<A, B> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self(A::truthy_default(), B::falsy_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TestFilter;
+    use super::*;
+
+    #[test]
+    fn matches() {
+        assert!(Implies(TestFilter::None, TestFilter::Panic).matches(&()));
+        assert!(Implies(TestFilter::Any, TestFilter::Any).matches(&()));
+        assert!(!Implies(TestFilter::Any, TestFilter::None).matches(&()));
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(
+            Implies(TestFilter::None, TestFilter::Panic).as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            Implies(TestFilter::Panic, TestFilter::Any).as_bool(),
+            Some(true)
+        );
+        assert_eq!(Implies(TestFilter::Panic, TestFilter::None).as_bool(), None);
+        assert_eq!(
+            Implies::<TestFilter, TestFilter>::falsy_default().as_bool(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn cost() {
+        assert_eq!(Implies(TestFilter::None, TestFilter::Panic).cost(), 1.0);
+        assert_eq!(Implies(TestFilter::Any, TestFilter::None).cost(), 2.0);
+        assert_eq!(
+            Implies(TestFilter::Any, TestFilter::Panic).selectivity(),
+            0.5
+        );
+        assert_eq!(
+            Implies(TestFilter::Panic, TestFilter::None).selectivity(),
+            0.5
+        );
+    }
+}
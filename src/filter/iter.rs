@@ -0,0 +1,164 @@
+use std::marker::PhantomData;
+
+use super::Filter;
+
+/// Marks [`MatchesItem`] impls for iterators over `&T`.
+#[derive(Debug)]
+pub enum ByRef {}
+
+/// Marks [`MatchesItem`] impls for iterators over `T`.
+#[derive(Debug)]
+pub enum ByValue {}
+
+/// Matches iterator items of type `I` with a [`Filter`].
+///
+/// `M` is either [`ByRef`] or [`ByValue`] and is inferred, so the same
+/// filter works for iterators over `&T` and `T`.
+pub trait MatchesItem<I, M> {
+    fn matches_item(&self, item: &I) -> bool;
+
+    fn matches_item_owned(&self, item: I) -> bool;
+}
+
+impl<T: ?Sized, F: Filter<T>> MatchesItem<&T, ByRef> for F {
+    #[inline]
+    fn matches_item(&self, item: &&T) -> bool {
+        self.matches(item)
+    }
+
+    #[inline]
+    fn matches_item_owned(&self, item: &T) -> bool {
+        self.matches(item)
+    }
+}
+
+impl<T, F: Filter<T>> MatchesItem<T, ByValue> for F {
+    #[inline]
+    fn matches_item(&self, item: &T) -> bool {
+        self.matches(item)
+    }
+
+    #[inline]
+    fn matches_item_owned(&self, item: T) -> bool {
+        self.matches_owned(item)
+    }
+}
+
+/// Filtering iterators with any [`Filter`].
+pub trait IteratorFilterExt: Iterator + Sized {
+    /// Yields only the items matching `filter`.
+    #[inline]
+    fn filter_by<F, M>(self, filter: &F) -> FilterBy<'_, Self, F, M>
+    where
+        F: MatchesItem<Self::Item, M>,
+    {
+        FilterBy {
+            iter: self,
+            filter,
+            mode: PhantomData,
+        }
+    }
+
+    /// Counts the items matching `filter`, passing owned items to it.
+    #[inline]
+    fn count_matching<F, M>(self, filter: &F) -> usize
+    where
+        F: MatchesItem<Self::Item, M>,
+    {
+        self.fold(0, |n, item| {
+            n + usize::from(filter.matches_item_owned(item))
+        })
+    }
+
+    /// Splits the items into those matching `filter` and those that don't.
+    #[inline]
+    fn partition_by<B, F, M>(self, filter: &F) -> (B, B)
+    where
+        B: Default + Extend<Self::Item>,
+        F: MatchesItem<Self::Item, M>,
+    {
+        self.partition(|item| filter.matches_item(item))
+    }
+
+    /// Returns the first item matching `filter`.
+    #[inline]
+    fn find_by<F, M>(&mut self, filter: &F) -> Option<Self::Item>
+    where
+        F: MatchesItem<Self::Item, M>,
+    {
+        self.find(|item| filter.matches_item(item))
+    }
+}
+
+impl<I: Iterator> IteratorFilterExt for I {}
+
+/// Iterator returned by [`IteratorFilterExt::filter_by`].
+#[derive(Debug)]
+pub struct FilterBy<'f, I, F, M> {
+    iter: I,
+    filter: &'f F,
+    mode: PhantomData<M>,
+}
+
+impl<I: Iterator, F: MatchesItem<I::Item, M>, M> Iterator for FilterBy<'_, I, F, M> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find(|item| self.filter.matches_item(item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I: DoubleEndedIterator, F: MatchesItem<I::Item, M>, M> DoubleEndedIterator
+    for FilterBy<'_, I, F, M>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.rfind(|item| self.filter.matches_item(item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{from_fn, Range, TextFilter};
+    use super::*;
+
+    #[test]
+    fn by_ref() {
+        let words = ["foo", "bar", "foobar"];
+        let f = TextFilter::starts_with("foo");
+
+        assert_eq!(
+            words.iter().copied().filter_by(&f).collect::<Vec<_>>(),
+            ["foo", "foobar"]
+        );
+        assert_eq!(words.iter().copied().count_matching(&f), 2);
+        assert_eq!(words.iter().copied().rev().find_by(&f), Some("foobar"));
+
+        let (foo, other): (Vec<_>, Vec<_>) = words.iter().copied().partition_by(&f);
+        assert_eq!(foo, ["foo", "foobar"]);
+        assert_eq!(other, ["bar"]);
+
+        let numbers = [1, 5, 10];
+        assert_eq!(numbers.iter().count_matching(&Range::ge(5)), 2);
+    }
+
+    #[test]
+    fn by_value() {
+        let f = Range::between(2, 4);
+
+        assert_eq!((0..10).filter_by(&f).collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!((0..10).filter_by(&f).next_back(), Some(4));
+        assert_eq!((0..10).count_matching(&f), 3);
+        assert_eq!((0..10).find_by(&f), Some(2));
+
+        let owned = from_fn(|s: &String| s.len() > 1);
+        let strings = ["a", "bb", "ccc"].map(String::from);
+        assert_eq!(strings.into_iter().count_matching(&owned), 2);
+    }
+}
@@ -1,7 +1,20 @@
-use super::{And, DataFilter, FalsyDefault, Filter, Not, Optimizable, Or, TruthyDefault};
+use super::normalize::{self, Connectives, Node};
+use super::{
+    And, Cost, DataFilter, Explain, Explanation, FalsyDefault, Filter, Infix, Not, Optimizable, Or,
+    Precedence, TriFilter, Truth, TruthyDefault,
+};
+use super::{Normalize, TooLarge};
 use datalink::links::Link;
 
-#[derive(Default, Debug)]
+/// A filter on the links of [`Data`](datalink::Data).
+///
+/// Serialized like [`DataFilter`], e.g. `{"key":{"text":"name"}}`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
 pub enum LinkFilter {
     #[default]
@@ -57,7 +70,96 @@ impl LinkFilter {
             _ => Self::Or(vec![self, f.into()].into()),
         }
     }
+
+    /// Whether both filters are equal after optimizing and normalizing them,
+    /// like [`DataFilter::equivalent`].
+    #[must_use]
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    fn canonical(&self) -> Self {
+        let mut f = self.clone();
+        f.optimize();
+        normalize::canonicalize(f)
+    }
+}
+
+impl Connectives for LinkFilter {
+    #[inline]
+    fn and(operands: Vec<Self>) -> Self {
+        Self::And(operands.into())
+    }
+    #[inline]
+    fn or(operands: Vec<Self>) -> Self {
+        Self::Or(operands.into())
+    }
+    #[inline]
+    fn not(self) -> Self {
+        !self
+    }
+    #[inline]
+    fn into_node(self) -> Node<Self> {
+        match self {
+            Self::And(mut and) => Node::And(std::mem::take(&mut *and)),
+            Self::Or(mut or) => Node::Or(std::mem::take(&mut *or)),
+            Self::Not(not) => Node::Not(not.0),
+            f => Node::Leaf(f),
+        }
+    }
+    #[inline]
+    fn negate_leaf(self) -> Self {
+        match self {
+            Self::Any => Self::None,
+            Self::None => Self::Any,
+            f => !f,
+        }
+    }
+    #[inline]
+    fn flatten_nested(self) -> Self {
+        match self {
+            Self::Key(f) => Self::Key(f.flatten()),
+            Self::Target(f) => Self::Target(f.flatten()),
+            f => f,
+        }
+    }
+    #[inline]
+    fn nnf_nested(self) -> Self {
+        match self {
+            Self::Key(f) => Self::Key(f.to_nnf()),
+            Self::Target(f) => Self::Target(f.to_nnf()),
+            f => f,
+        }
+    }
+    #[inline]
+    fn canonicalize_nested(self) -> Self {
+        match self {
+            Self::Key(f) => Self::Key(normalize::canonicalize(f)),
+            Self::Target(f) => Self::Target(normalize::canonicalize(f)),
+            f => f,
+        }
+    }
+}
+
+impl Normalize for LinkFilter {
+    #[inline]
+    fn flatten(self) -> Self {
+        normalize::flatten(self)
+    }
+    #[inline]
+    fn to_nnf(self) -> Self {
+        normalize::to_nnf(self)
+    }
+    #[inline]
+    fn to_dnf(self, limit: usize) -> Result<Self, TooLarge> {
+        normalize::to_dnf(self, limit)
+    }
+    #[inline]
+    fn to_cnf(self, limit: usize) -> Result<Self, TooLarge> {
+        normalize::to_cnf(self, limit)
+    }
 }
+
 impl<L: Link + ?Sized> Filter<L> for LinkFilter {
     #[inline]
     fn matches(&self, l: &L) -> bool {
@@ -65,7 +167,7 @@ impl<L: Link + ?Sized> Filter<L> for LinkFilter {
         match self {
             E::Any => true,
             E::None => false,
-            E::Not(f) => !f.matches(l),
+            E::Not(f) => f.matches(l),
             E::And(f) => f.matches(l),
             E::Or(f) => f.matches(l),
             E::Key(f) => l.key().is_some_and(|k| f.matches(k)),
@@ -74,6 +176,140 @@ impl<L: Link + ?Sized> Filter<L> for LinkFilter {
     }
 }
 
+impl<L: Link + ?Sized> TriFilter<L> for LinkFilter {
+    /// Evaluates the filter, returning [`Truth::Unknown`] for key filters on
+    /// unkeyed links.
+    #[inline]
+    fn eval(&self, l: &L) -> Truth {
+        use LinkFilter as E;
+        match self {
+            E::Any => Truth::True,
+            E::None => Truth::False,
+            E::Not(f) => TriFilter::<L>::eval(f, l),
+            E::And(f) => TriFilter::<L>::eval(f, l),
+            E::Or(f) => TriFilter::<L>::eval(f, l),
+            E::Key(f) => l.key().map_or(Truth::Unknown, |k| f.eval(k)),
+            E::Target(f) => f.eval(l.target()),
+        }
+    }
+}
+
+impl<L: Link + ?Sized> Explain<L> for LinkFilter {
+    fn explain(&self, l: &L) -> Explanation {
+        use LinkFilter as E;
+        match self {
+            E::Any | E::None => Explanation::new(self.matches(l), self.description()),
+            E::Not(f) => Explain::<L>::explain(&**f, l),
+            E::And(f) => Explain::<L>::explain(f, l),
+            E::Or(f) => Explain::<L>::explain(f, l),
+            E::Key(f) => match l.key() {
+                Some(key) => {
+                    let inner = f.explain(key);
+                    let detail = if inner.matched() {
+                        "link key matched"
+                    } else {
+                        "link key did not match"
+                    };
+                    Explanation::new(inner.matched(), detail).with_children(vec![inner])
+                }
+                None => Explanation::new(false, "link has no key"),
+            },
+            E::Target(f) => {
+                let inner = f.explain(l.target());
+                let detail = if inner.matched() {
+                    "link target matched"
+                } else {
+                    "link target did not match"
+                };
+                Explanation::new(inner.matched(), detail).with_children(vec![inner])
+            }
+        }
+    }
+
+    #[inline]
+    fn describe(&self) -> String {
+        self.description()
+    }
+}
+
+impl LinkFilter {
+    pub(crate) fn description(&self) -> String {
+        use LinkFilter as E;
+        match self {
+            E::Any => "any link".to_owned(),
+            E::None => "no link".to_owned(),
+            E::Not(f) => format!("not {}", f.0.description()),
+            E::And(f) => format!("all of {}", f.len()),
+            E::Or(f) => format!("any of {}", f.len()),
+            E::Key(f) => format!("key {}", f.description()),
+            E::Target(f) => format!("target {}", f.description()),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use LinkFilter as E;
+        match self {
+            E::Any => f.write_str("any"),
+            E::None => f.write_str("none"),
+            E::Not(not) => not.fmt(f),
+            E::And(and) => and.fmt(f),
+            E::Or(or) => or.fmt(f),
+            E::Key(k) => write!(f, "key({k})"),
+            E::Target(t) => write!(f, "target({t})"),
+        }
+    }
+}
+
+impl Infix for LinkFilter {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        match self {
+            LinkFilter::Not(not) => not.precedence(),
+            LinkFilter::And(and) => and.precedence(),
+            LinkFilter::Or(or) => or.precedence(),
+            _ => Precedence::Atom,
+        }
+    }
+}
+
+impl Cost for LinkFilter {
+    fn cost(&self) -> f64 {
+        use LinkFilter as E;
+        match self {
+            E::Any | E::None => 0.0,
+            E::Key(f) | E::Target(f) => f.cost(),
+            E::And(f) => f.cost(),
+            E::Or(f) => f.cost(),
+            E::Not(f) => f.cost(),
+        }
+    }
+
+    fn selectivity(&self) -> f64 {
+        use LinkFilter as E;
+        match self {
+            E::Any => 1.0,
+            E::None => 0.0,
+            E::Key(f) | E::Target(f) => f.selectivity(),
+            E::And(f) => f.selectivity(),
+            E::Or(f) => f.selectivity(),
+            E::Not(f) => f.selectivity(),
+        }
+    }
+
+    fn reorder(&mut self) {
+        use LinkFilter as E;
+        match self {
+            E::Any | E::None => {}
+            E::Key(f) | E::Target(f) => f.reorder(),
+            E::And(f) => f.reorder(),
+            E::Or(f) => f.reorder(),
+            E::Not(f) => f.reorder(),
+        }
+    }
+}
+
 impl Optimizable for LinkFilter {
     #[inline]
     fn as_bool(&self) -> Option<bool> {
@@ -0,0 +1,243 @@
+use std::fmt;
+
+/// Rewrites a filter into an equivalent canonical shape.
+///
+/// [`flatten`](Normalize::flatten) and [`to_nnf`](Normalize::to_nnf) also
+/// apply to nested filters, like the [`LinkFilter`](crate::LinkFilter) of
+/// [`DataFilter::Linked`](crate::DataFilter::Linked), while the clause forms
+/// treat those as leaves.
+pub trait Normalize: Sized {
+    /// Splices nested `And`s and `Or`s into their parents and removes double
+    /// negations.
+    #[must_use]
+    fn flatten(self) -> Self;
+
+    /// Pushes negations down to the leaves, using De Morgan's laws.
+    #[must_use]
+    fn to_nnf(self) -> Self;
+
+    /// Converts to an `Or` of `And`s of leaves, failing if that takes more
+    /// than `limit` clauses.
+    fn to_dnf(self, limit: usize) -> Result<Self, TooLarge>;
+
+    /// Converts to an `And` of `Or`s of leaves, failing if that takes more
+    /// than `limit` clauses.
+    fn to_cnf(self, limit: usize) -> Result<Self, TooLarge>;
+}
+
+/// A normal form would exceed its clause limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TooLarge {
+    limit: usize,
+}
+
+impl TooLarge {
+    #[inline]
+    #[must_use]
+    pub const fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "normal form exceeds the limit of {} clauses", self.limit)
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// The outermost connective of a filter.
+pub(crate) enum Node<F> {
+    And(Vec<F>),
+    Or(Vec<F>),
+    Not(F),
+    Leaf(F),
+}
+
+/// A filter built from `And`, `Or` and `Not` over leaves.
+pub(crate) trait Connectives: Sized + Clone {
+    fn and(operands: Vec<Self>) -> Self;
+    fn or(operands: Vec<Self>) -> Self;
+    fn not(self) -> Self;
+    fn into_node(self) -> Node<Self>;
+
+    /// Negates a leaf, e.g. turning `Any` into `None`.
+    #[inline]
+    fn negate_leaf(self) -> Self {
+        self.not()
+    }
+
+    /// Flattens the filters nested in a leaf.
+    #[inline]
+    fn flatten_nested(self) -> Self {
+        self
+    }
+
+    /// Converts the filters nested in a leaf to negation normal form.
+    #[inline]
+    fn nnf_nested(self) -> Self {
+        self
+    }
+
+    /// Canonicalizes a leaf and the filters nested in it.
+    #[inline]
+    fn canonicalize_nested(self) -> Self {
+        self
+    }
+}
+
+pub(crate) fn flatten<F: Connectives>(f: F) -> F {
+    match f.into_node() {
+        Node::And(operands) => {
+            let mut flat = Vec::with_capacity(operands.len());
+            for f in operands {
+                match flatten(f).into_node() {
+                    Node::And(mut inner) => flat.append(&mut inner),
+                    node => flat.push(from_node(node)),
+                }
+            }
+            single_or(flat, F::and)
+        }
+        Node::Or(operands) => {
+            let mut flat = Vec::with_capacity(operands.len());
+            for f in operands {
+                match flatten(f).into_node() {
+                    Node::Or(mut inner) => flat.append(&mut inner),
+                    node => flat.push(from_node(node)),
+                }
+            }
+            single_or(flat, F::or)
+        }
+        Node::Not(f) => match flatten(f).into_node() {
+            Node::Not(f) => f,
+            node => from_node(node).not(),
+        },
+        Node::Leaf(f) => f.flatten_nested(),
+    }
+}
+
+/// Flattens a filter and sorts and dedupes its operands, so filters that
+/// only differ in operand order become equal.
+pub(crate) fn canonicalize<F: Connectives + fmt::Display + PartialEq>(f: F) -> F {
+    sort(flatten(f))
+}
+
+fn sort<F: Connectives + fmt::Display + PartialEq>(f: F) -> F {
+    match f.into_node() {
+        Node::And(operands) => single_or(sort_operands(operands), F::and),
+        Node::Or(operands) => single_or(sort_operands(operands), F::or),
+        Node::Not(f) => sort(f).not(),
+        Node::Leaf(f) => f.canonicalize_nested(),
+    }
+}
+
+/// Sorts operands by their [`Display`](fmt::Display) form and removes
+/// duplicates.
+fn sort_operands<F: Connectives + fmt::Display + PartialEq>(operands: Vec<F>) -> Vec<F> {
+    let mut operands: Vec<F> = operands.into_iter().map(sort).collect();
+    operands.sort_by_cached_key(ToString::to_string);
+    operands.dedup();
+    operands
+}
+
+pub(crate) fn to_nnf<F: Connectives>(f: F) -> F {
+    flatten(nnf(f, false))
+}
+
+fn nnf<F: Connectives>(f: F, negate: bool) -> F {
+    match f.into_node() {
+        Node::And(operands) => {
+            let operands = operands.into_iter().map(|f| nnf(f, negate)).collect();
+            if negate {
+                F::or(operands)
+            } else {
+                F::and(operands)
+            }
+        }
+        Node::Or(operands) => {
+            let operands = operands.into_iter().map(|f| nnf(f, negate)).collect();
+            if negate {
+                F::and(operands)
+            } else {
+                F::or(operands)
+            }
+        }
+        Node::Not(f) => nnf(f, !negate),
+        Node::Leaf(f) if negate => f.nnf_nested().negate_leaf(),
+        Node::Leaf(f) => f.nnf_nested(),
+    }
+}
+
+pub(crate) fn to_dnf<F: Connectives>(f: F, limit: usize) -> Result<F, TooLarge> {
+    let clauses = clauses(to_nnf(f), true, limit)?;
+    let clauses = clauses.into_iter().map(|c| single_or(c, F::and)).collect();
+    Ok(single_or(clauses, F::or))
+}
+
+pub(crate) fn to_cnf<F: Connectives>(f: F, limit: usize) -> Result<F, TooLarge> {
+    let clauses = clauses(to_nnf(f), false, limit)?;
+    let clauses = clauses.into_iter().map(|c| single_or(c, F::or)).collect();
+    Ok(single_or(clauses, F::and))
+}
+
+/// Splits a filter in negation normal form into clauses, conjunctions if
+/// `dnf` and disjunctions otherwise.
+fn clauses<F: Connectives>(f: F, dnf: bool, limit: usize) -> Result<Vec<Vec<F>>, TooLarge> {
+    let (joined, distributed) = match f.into_node() {
+        Node::Or(operands) if dnf => (operands, false),
+        Node::And(operands) if !dnf => (operands, false),
+        Node::And(operands) | Node::Or(operands) => (operands, true),
+        node => return Ok(vec![vec![from_node(node)]]),
+    };
+    let mut result = if distributed {
+        vec![Vec::new()]
+    } else {
+        Vec::new()
+    };
+    for f in joined {
+        let clauses = clauses(f, dnf, limit)?;
+        if distributed {
+            if result.len().saturating_mul(clauses.len()) > limit {
+                return Err(TooLarge { limit });
+            }
+            result = result
+                .iter()
+                .flat_map(|r| {
+                    clauses.iter().map(move |c| {
+                        let mut r = r.clone();
+                        r.extend(c.iter().cloned());
+                        r
+                    })
+                })
+                .collect();
+        } else {
+            result.extend(clauses);
+            if result.len() > limit {
+                return Err(TooLarge { limit });
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[inline]
+fn from_node<F: Connectives>(node: Node<F>) -> F {
+    match node {
+        Node::And(operands) => F::and(operands),
+        Node::Or(operands) => F::or(operands),
+        Node::Not(f) => f.not(),
+        Node::Leaf(f) => f,
+    }
+}
+
+/// Unwraps a single operand instead of joining it with `join`.
+#[inline]
+fn single_or<F>(mut operands: Vec<F>, join: fn(Vec<F>) -> F) -> F {
+    if operands.len() == 1 {
+        if let Some(f) = operands.pop() {
+            return f;
+        }
+    }
+    join(operands)
+}
@@ -1,6 +1,14 @@
-use super::{FalsyDefault, Filter, Optimizable, TruthyDefault};
+use super::display::{fmt_operand, Infix, Precedence};
+use super::{
+    Cost, Explain, Explanation, FalsyDefault, Filter, Optimizable, TriFilter, Truth, TruthyDefault,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Not<F>(pub F);
 
 impl<F: Filter<T>, T: ?Sized> Filter<T> for Not<F> {
@@ -9,6 +17,55 @@ impl<F: Filter<T>, T: ?Sized> Filter<T> for Not<F> {
         !self.0.matches(obj)
     }
 }
+impl<F: TriFilter<T>, T: ?Sized> TriFilter<T> for Not<F> {
+    #[inline]
+    fn eval(&self, obj: &T) -> Truth {
+        !self.0.eval(obj)
+    }
+}
+
+impl<F: Explain<T>, T: ?Sized> Explain<T> for Not<F> {
+    fn explain(&self, obj: &T) -> Explanation {
+        let inner = self.0.explain(obj);
+        Explanation::new(!inner.matched(), "not").with_children(vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        format!("not {}", self.0.describe())
+    }
+}
+
+impl<F: Cost> Cost for Not<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.0.cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        1.0 - self.0.selectivity()
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.0.reorder();
+    }
+}
+
+impl<F: Infix> std::fmt::Display for Not<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("!")?;
+        fmt_operand(f, &self.0, Precedence::Not)
+    }
+}
+
+impl<F: Infix> Infix for Not<F> {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        Precedence::Not
+    }
+}
+
 impl<F: Optimizable> Optimizable for Not<F> {
     #[inline]
     fn as_bool(&self) -> Option<bool> {
@@ -1,6 +1,15 @@
-use super::{FalsyDefault, Filter, Optimizable, TruthyDefault};
-
-#[derive(Debug)]
+use super::cost;
+use super::display::{fmt_operands, Infix, Precedence};
+use super::{
+    Cost, Explain, Explanation, FalsyDefault, Filter, Optimizable, TriFilter, Truth, TruthyDefault,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Or<F>(Vec<F>);
 
 impl<F> From<Vec<F>> for Or<F> {
@@ -32,6 +41,81 @@ impl<F: Filter<T>, T: ?Sized> Filter<T> for Or<F> {
     }
 }
 
+impl<F: TriFilter<T>, T: ?Sized> TriFilter<T> for Or<F> {
+    #[inline]
+    fn eval(&self, obj: &T) -> Truth {
+        let mut result = Truth::False;
+        for f in &self.0 {
+            match f.eval(obj) {
+                Truth::True => return Truth::True,
+                Truth::Unknown => result = Truth::Unknown,
+                Truth::False => {}
+            }
+        }
+        result
+    }
+}
+
+impl<F: Explain<T>, T: ?Sized> Explain<T> for Or<F> {
+    fn explain(&self, obj: &T) -> Explanation {
+        let mut children = Vec::with_capacity(self.0.len());
+        let mut decided = false;
+        for f in &self.0 {
+            if decided {
+                children.push(Explanation::skipped(f.describe()));
+                continue;
+            }
+            let e = f.explain(obj);
+            decided = e.matched();
+            children.push(e);
+        }
+        Explanation::new(decided, self.describe()).with_children(children)
+    }
+
+    fn describe(&self) -> String {
+        format!("any of {}", self.0.len())
+    }
+}
+
+impl<F: Cost> Cost for Or<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost::sequence(&self.0, true).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost::sequence(&self.0, true).1
+    }
+
+    /// Sorts the operands so those most likely to short circuit for their
+    /// cost run first.
+    #[inline]
+    fn reorder(&mut self) {
+        cost::sort(&mut self.0, true);
+    }
+}
+
+impl<F: Infix> std::fmt::Display for Or<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.as_slice() {
+            [] => f.write_str("or()"),
+            [only] => write!(f, "or({only})"),
+            operands => fmt_operands(f, operands, " | ", Precedence::Or),
+        }
+    }
+}
+
+impl<F: Infix> Infix for Or<F> {
+    #[inline]
+    fn precedence(&self) -> Precedence {
+        match self.0.as_slice() {
+            [] | [_] => Precedence::Atom,
+            _ => Precedence::Or,
+        }
+    }
+}
+
 impl<F: Optimizable + TruthyDefault> Optimizable for Or<F> {
     #[inline]
     fn as_bool(&self) -> Option<bool> {
@@ -65,6 +149,7 @@ impl<F: Optimizable + TruthyDefault> Optimizable for Or<F> {
 
         // if any filter is unconditionally true, the whole filter is true
         if short_circuit {
+            #[allow(clippy::swap_with_temporary)]
             std::mem::swap(self, &mut Self::truthy_default());
         }
     }
@@ -85,8 +170,9 @@ impl<F> FalsyDefault for Or<F> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
-    use super::super::TestFilter;
+    use super::super::{Outcome, TestFilter};
     use super::*;
 
     #[test]
@@ -124,4 +210,28 @@ mod tests {
 
         assert_eq!(f.as_bool().unwrap(), false);
     }
+
+    #[test]
+    fn kleene() {
+        let f = Or::from(vec![TestFilter::None, TestFilter::Panic]);
+        assert_eq!(f.eval(&()), Truth::Unknown);
+
+        let f = Or::from(vec![TestFilter::Panic, TestFilter::Any]);
+        assert_eq!(f.eval(&()), Truth::True);
+
+        let f = Or::from(vec![TestFilter::None, TestFilter::None]);
+        assert_eq!(f.eval(&()), Truth::False);
+    }
+
+    #[test]
+    fn explain() {
+        let f = Or::from(vec![TestFilter::None, TestFilter::Any, TestFilter::Panic]);
+        let e = f.explain(&());
+
+        assert!(e.matched());
+        assert_eq!(
+            e.children.iter().map(|e| e.outcome).collect::<Vec<_>>(),
+            [Outcome::Failed, Outcome::Matched, Outcome::Skipped]
+        );
+    }
 }
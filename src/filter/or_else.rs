@@ -0,0 +1,100 @@
+use super::cost;
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if either filter matches, evaluating `B` only if `A` didn't match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OrElse<A, B>(pub A, pub B);
+
+impl<A: Filter<T>, B: Filter<T>, T: ?Sized> Filter<T> for OrElse<A, B> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.0.matches(obj) || self.1.matches(obj)
+    }
+}
+
+impl<A: Optimizable, B: Optimizable> Optimizable for OrElse<A, B> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        match (self.0.as_bool(), self.1.as_bool()) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        self.0.optimize();
+        self.1.optimize();
+    }
+}
+
+/// `B` is only evaluated if `A` didn't match.
+impl<A: Cost, B: Cost> Cost for OrElse<A, B> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost::sequence([&self.0 as &dyn Cost, &self.1], true).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost::sequence([&self.0 as &dyn Cost, &self.1], true).1
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.0.reorder();
+        self.1.reorder();
+    }
+}
+
+impl<A: TruthyDefault, B: TruthyDefault> TruthyDefault for OrElse<A, B> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self(A::truthy_default(), B::truthy_default())
+    }
+}
+
+impl<A: FalsyDefault, B: FalsyDefault> FalsyDefault for OrElse<A, B> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self(A::falsy_default(), B::falsy_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TestFilter;
+    use super::*;
+
+    #[test]
+    fn constant() {
+        assert_eq!(
+            OrElse(TestFilter::None, TestFilter::None).as_bool(),
+            Some(false)
+        );
+        assert_eq!(
+            OrElse(TestFilter::None, TestFilter::Any).as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            OrElse(TestFilter::Panic, TestFilter::Any).as_bool(),
+            Some(true)
+        );
+        assert_eq!(OrElse(TestFilter::Panic, TestFilter::None).as_bool(), None);
+    }
+
+    #[test]
+    fn defaults() {
+        let f = OrElse::<TestFilter, TestFilter>::truthy_default();
+        assert_eq!(f.as_bool(), Some(true));
+
+        let f = OrElse::<TestFilter, TestFilter>::falsy_default();
+        assert_eq!(f.as_bool(), Some(false));
+    }
+
+    #[test]
+    fn short_circuit() {
+        assert!(OrElse(TestFilter::Any, TestFilter::Panic).matches(&()));
+    }
+}
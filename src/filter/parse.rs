@@ -0,0 +1,648 @@
+use std::fmt;
+use std::ops::{Bound, Range as Span};
+use std::str::FromStr;
+
+use super::normalize::Connectives;
+use super::{DataFilter, LinkFilter, Range, TextFilter, TextSet};
+use datalink::id::ID;
+
+mod lexer;
+use lexer::{tokenize, Token};
+
+/// An error from parsing a filter, pointing at the offending bytes of the
+/// input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    span: Span<usize>,
+}
+
+impl ParseError {
+    #[inline]
+    fn new(kind: ParseErrorKind, span: Span<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// The byte range of the input the error refers to.
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Span<usize> {
+        self.span.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    UnterminatedString,
+    InvalidEscape,
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+    UnknownName(String),
+    InvalidNumber,
+    InvalidId,
+    #[cfg(feature = "regex")]
+    InvalidRegex(String),
+    /// Operators or filters are nested deeper than the parser allows.
+    TooDeep,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            ParseErrorKind::UnterminatedString => f.write_str("unterminated string"),
+            ParseErrorKind::InvalidEscape => f.write_str("invalid escape sequence"),
+            ParseErrorKind::Unexpected { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            ParseErrorKind::UnknownName(name) => write!(f, "unknown filter `{name}`"),
+            ParseErrorKind::InvalidNumber => f.write_str("invalid number"),
+            ParseErrorKind::InvalidId => f.write_str("invalid id"),
+            #[cfg(feature = "regex")]
+            ParseErrorKind::InvalidRegex(e) => write!(f, "invalid regex: {e}"),
+            ParseErrorKind::TooDeep => write!(f, "nested deeper than {MAX_DEPTH} levels"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+/// Parses a [`DataFilter`] from a query.
+///
+/// Operators are `!`/`not`, `&`/`and` and `|`/`or`, binding in that order,
+/// with juxtaposed operands joined by `and`. Filters are written as
+/// `name(...)` or `name:...`, e.g. `text:"foo"`, `id:<hex>`, `int(1..=5)` or
+/// `linked(key:"name" target:text(contains("foo")))`, and a bare string is
+/// short for `text(...)`. `and(...)` and `or(...)` hold a single operand, or
+/// none with `and()` and `or()`. The [`Display`](fmt::Display) output of a
+/// filter parses back to an equal filter.
+#[inline]
+pub fn parse(input: &str) -> Result<DataFilter> {
+    input.parse()
+}
+
+impl FromStr for DataFilter {
+    type Err = ParseError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Parser::new(s)?.finish(Parser::data_atom)
+    }
+}
+
+impl FromStr for LinkFilter {
+    type Err = ParseError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Parser::new(s)?.finish(Parser::link_atom)
+    }
+}
+
+/// How deeply operators and filters may nest, so that hostile input can't
+/// overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+type Atom<'a, N> = fn(&mut Parser<'a>) -> Result<N>;
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, Span<usize>)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(input)?,
+            pos: 0,
+            depth: 0,
+        })
+    }
+
+    fn finish<N: Connectives>(mut self, atom: Atom<'a, N>) -> Result<N> {
+        let node = self.expr(atom)?;
+        if *self.peek() != Token::End {
+            return Err(self.unexpected("end of input"));
+        }
+        Ok(node)
+    }
+
+    #[inline]
+    fn peek(&self) -> &Token<'a> {
+        self.peek_at(0)
+    }
+
+    #[inline]
+    fn peek_at(&self, offset: usize) -> &Token<'a> {
+        let i = (self.pos + offset).min(self.tokens.len() - 1);
+        &self.tokens[i].0
+    }
+
+    #[inline]
+    fn span(&self) -> Span<usize> {
+        self.tokens[self.pos].1.clone()
+    }
+
+    #[inline]
+    fn bump(&mut self) {
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+    }
+
+    #[inline]
+    fn eat(&mut self, token: &Token<'_>) -> bool {
+        let found = self.peek() == token;
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    #[inline]
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    #[inline]
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &Token<'_>, expected: &'static str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    /// Runs `inner` one nesting level deeper, failing with
+    /// [`ParseErrorKind::TooDeep`] at `span` past [`MAX_DEPTH`].
+    fn nested<T>(
+        &mut self,
+        span: Span<usize>,
+        inner: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(ParseError::new(ParseErrorKind::TooDeep, span));
+        }
+        self.depth += 1;
+        let result = inner(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        let found = self.peek().describe();
+        ParseError::new(ParseErrorKind::Unexpected { expected, found }, self.span())
+    }
+
+    fn expr<N: Connectives>(&mut self, atom: Atom<'a, N>) -> Result<N> {
+        let mut operands = vec![self.conjunction(atom)?];
+        while self.eat(&Token::Pipe) || self.eat_keyword("or") {
+            operands.push(self.conjunction(atom)?);
+        }
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => N::or(operands),
+        })
+    }
+
+    fn conjunction<N: Connectives>(&mut self, atom: Atom<'a, N>) -> Result<N> {
+        let mut operands = vec![self.unary(atom)?];
+        loop {
+            let joined = self.eat(&Token::Amp) || self.eat_keyword("and");
+            if !joined && !self.starts_operand() {
+                break;
+            }
+            operands.push(self.unary(atom)?);
+        }
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => N::and(operands),
+        })
+    }
+
+    #[inline]
+    fn starts_operand(&self) -> bool {
+        match self.peek() {
+            Token::LParen | Token::Bang | Token::Str(_) => true,
+            Token::Word(_) => !self.is_keyword("or"),
+            _ => false,
+        }
+    }
+
+    fn unary<N: Connectives>(&mut self, atom: Atom<'a, N>) -> Result<N> {
+        let span = self.span();
+        if self.eat(&Token::Bang) || self.eat_keyword("not") {
+            return Ok(self.nested(span, |p| p.unary(atom))?.not());
+        }
+        if self.eat(&Token::LParen) {
+            let node = self.nested(span, |p| p.expr(atom))?;
+            self.expect(&Token::RParen, "`)`")?;
+            return Ok(node);
+        }
+        // `and()` and `or(f)` are the empty and single operand forms
+        if *self.peek_at(1) == Token::LParen {
+            let join: Option<fn(Vec<N>) -> N> = match self.peek() {
+                Token::Word("and") => Some(N::and),
+                Token::Word("or") => Some(N::or),
+                _ => None,
+            };
+            if let Some(join) = join {
+                self.pos += 2;
+                if self.eat(&Token::RParen) {
+                    return Ok(join(Vec::new()));
+                }
+                let node = self.nested(span, |p| p.expr(atom))?;
+                self.expect(&Token::RParen, "`)`")?;
+                return Ok(join(vec![node]));
+            }
+        }
+        atom(self)
+    }
+
+    /// Parses the argument of a filter, either `:arg` or `(arg)`.
+    fn arg<T>(&mut self, inner: fn(&mut Self) -> Result<T>) -> Result<T> {
+        if self.eat(&Token::Colon) {
+            return inner(self);
+        }
+        self.expect(&Token::LParen, "`(` or `:`")?;
+        let arg = inner(self)?;
+        self.expect(&Token::RParen, "`)`")?;
+        Ok(arg)
+    }
+
+    /// Parses a filter argument, either `:operand` or `(expression)`.
+    fn expr_arg<N: Connectives>(&mut self, atom: Atom<'a, N>) -> Result<N> {
+        let span = self.span();
+        if self.eat(&Token::Colon) {
+            return self.nested(span, |p| p.unary(atom));
+        }
+        self.expect(&Token::LParen, "`(` or `:`")?;
+        let node = self.nested(span, |p| p.expr(atom))?;
+        self.expect(&Token::RParen, "`)`")?;
+        Ok(node)
+    }
+
+    fn data_atom(&mut self) -> Result<DataFilter> {
+        let span = self.span();
+        let name = match self.peek() {
+            Token::Str(_) => return self.string().map(DataFilter::text),
+            Token::Word(name) => *name,
+            _ => return Err(self.unexpected("filter")),
+        };
+        self.bump();
+        match name {
+            "any" => Ok(DataFilter::Any),
+            "none" => Ok(DataFilter::None),
+            "unique" => Ok(DataFilter::Unique),
+            "text" => self.arg(Self::text),
+            "id" => self.arg(Self::id).map(DataFilter::Id),
+            "not_id" => self.arg(Self::id).map(DataFilter::NotId),
+            "bool" => self.arg(Self::boolean).map(DataFilter::Bool),
+            "number" => self.arg(Self::range).map(DataFilter::Number),
+            "int" => self.arg(Self::range).map(DataFilter::Int),
+            "float" => self.arg(Self::range).map(DataFilter::Float),
+            "linked" => self.expr_arg(Self::link_atom).map(DataFilter::linked),
+            _ => Err(ParseError::new(
+                ParseErrorKind::UnknownName(name.to_owned()),
+                span,
+            )),
+        }
+    }
+
+    fn link_atom(&mut self) -> Result<LinkFilter> {
+        let span = self.span();
+        let Token::Word(name) = *self.peek() else {
+            return Err(self.unexpected("link filter"));
+        };
+        self.bump();
+        match name {
+            "any" => Ok(LinkFilter::Any),
+            "none" => Ok(LinkFilter::None),
+            "key" => self.expr_arg(Self::data_atom).map(LinkFilter::Key),
+            "target" => self.expr_arg(Self::data_atom).map(LinkFilter::Target),
+            _ => Err(ParseError::new(
+                ParseErrorKind::UnknownName(name.to_owned()),
+                span,
+            )),
+        }
+    }
+
+    fn text(&mut self) -> Result<DataFilter> {
+        if self.is_keyword("set") && *self.peek_at(1) == Token::LParen {
+            self.bump();
+            return self.arg(Self::text_set).map(DataFilter::TextSet);
+        }
+        self.text_filter().map(DataFilter::Text)
+    }
+
+    fn text_set(&mut self) -> Result<TextSet> {
+        let mut set = TextSet::new();
+        while *self.peek() != Token::RParen {
+            set.extend([self.text_filter()?]);
+            if !self.eat(&Token::Comma) {
+                break;
+            }
+        }
+        Ok(set)
+    }
+
+    fn text_filter(&mut self) -> Result<TextFilter> {
+        let span = self.span();
+        let name = match self.peek() {
+            Token::Str(_) => return self.string().map(TextFilter::equals),
+            Token::Word(name) => *name,
+            _ => return Err(self.unexpected("text filter")),
+        };
+        self.bump();
+        match name {
+            "contains" => self.arg(Self::string).map(TextFilter::contains),
+            "starts_with" => self.arg(Self::string).map(TextFilter::starts_with),
+            "ends_with" => self.arg(Self::string).map(TextFilter::ends_with),
+            "glob" => self.arg(Self::string).map(TextFilter::glob),
+            #[cfg(feature = "regex")]
+            "regex" => self.arg(Self::regex),
+            "ignore_case" => self
+                .nested(span, |p| p.arg(Self::text_filter))
+                .map(TextFilter::ignore_case),
+            "ignore_ascii_case" => self
+                .nested(span, |p| p.arg(Self::text_filter))
+                .map(TextFilter::ignore_ascii_case),
+            _ => Err(ParseError::new(
+                ParseErrorKind::UnknownName(name.to_owned()),
+                span,
+            )),
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex(&mut self) -> Result<TextFilter> {
+        let span = self.span();
+        let pattern = self.string()?;
+        TextFilter::regex(pattern)
+            .map_err(|e| ParseError::new(ParseErrorKind::InvalidRegex(e.to_string()), span))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.peek() {
+            Token::Str(s) => {
+                let s = s.clone();
+                self.bump();
+                Ok(s)
+            }
+            _ => Err(self.unexpected("string")),
+        }
+    }
+
+    fn id(&mut self) -> Result<ID> {
+        let Token::Word(word) = *self.peek() else {
+            return Err(self.unexpected("id"));
+        };
+        let id = word
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::InvalidId, self.span()))?;
+        self.bump();
+        Ok(id)
+    }
+
+    fn boolean(&mut self) -> Result<bool> {
+        let value = match self.peek() {
+            Token::Word("true") => true,
+            Token::Word("false") => false,
+            _ => return Err(self.unexpected("`true` or `false`")),
+        };
+        self.bump();
+        Ok(value)
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T> {
+        let Token::Word(word) = *self.peek() else {
+            return Err(self.unexpected("number"));
+        };
+        let number = word
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, self.span()))?;
+        self.bump();
+        Ok(number)
+    }
+
+    /// Parses a range like `1..`, `1<..5` or `..=5`.
+    fn range<T: FromStr>(&mut self) -> Result<Range<T>> {
+        let start = match self.peek() {
+            Token::Word(_) => {
+                let start = self.number()?;
+                if self.eat(&Token::Lt) {
+                    Bound::Excluded(start)
+                } else {
+                    Bound::Included(start)
+                }
+            }
+            _ => Bound::Unbounded,
+        };
+        self.expect(&Token::DotDot, "`..`")?;
+        let end = if self.eat(&Token::Eq) {
+            Bound::Included(self.number()?)
+        } else if matches!(self.peek(), Token::Word(_)) {
+            Bound::Excluded(self.number()?)
+        } else {
+            Bound::Unbounded
+        };
+        Ok(Range::new(start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn round_trip(f: &DataFilter) {
+        let s = f.to_string();
+        let parsed = parse(&s).unwrap_or_else(|e| panic!("{s}: {e}"));
+        assert_eq!(&parsed, f, "{s}");
+    }
+
+    #[test]
+    fn query() {
+        let f =
+            parse(r#"text:"foo" and not (id:1 or linked(key:"name" target:text:"bar"))"#).unwrap();
+        let expected = DataFilter::text("foo")
+            & !(DataFilter::id(ID::from(1))
+                | DataFilter::linked(
+                    LinkFilter::key(DataFilter::text("name"))
+                        & LinkFilter::target(DataFilter::text("bar")),
+                ));
+        assert_eq!(f.to_string(), expected.to_string());
+
+        let f = parse("unique any | NONE | none").unwrap_err();
+        assert_eq!(f.span(), 13..17);
+        assert_eq!(
+            parse(r#""a" OR "b""#).unwrap().to_string(),
+            r#"text("a") | text("b")"#
+        );
+    }
+
+    #[test]
+    fn display() {
+        round_trip(&(DataFilter::any() & DataFilter::none()));
+        round_trip(&(!DataFilter::unique() | DataFilter::not_id(ID::from(0xab))));
+        round_trip(&!(DataFilter::int(1..) | DataFilter::bool(false)));
+        round_trip(&!!DataFilter::float(..=0.5));
+        round_trip(&DataFilter::number(Range::new(
+            Bound::Excluded(-1.5),
+            Bound::Excluded(f64::INFINITY),
+        )));
+        round_trip(&DataFilter::text(TextFilter::glob("a?\"\\\n\u{301}")));
+        round_trip(&DataFilter::text(TextFilter::contains("Foo").ignore_case()));
+        round_trip(&DataFilter::text(
+            TextFilter::starts_with("Foo").ignore_ascii_case(),
+        ));
+        round_trip(&DataFilter::TextSet(TextSet::from_iter([
+            TextFilter::equals("a"),
+            TextFilter::contains("b"),
+            TextFilter::ends_with("c"),
+        ])));
+        round_trip(&DataFilter::linked(
+            !LinkFilter::key(DataFilter::text("a") | DataFilter::text("b"))
+                | LinkFilter::target(DataFilter::Unique),
+        ));
+        round_trip(&DataFilter::And(Vec::new().into()));
+        round_trip(&(DataFilter::Or(Vec::new().into()) & DataFilter::Unique));
+
+        let [a, b, c] = ["a", "b", "c"].map(DataFilter::text);
+        let and = |operands: Vec<DataFilter>| DataFilter::And(operands.into());
+        let or = |operands: Vec<DataFilter>| DataFilter::Or(operands.into());
+        // nested operators of the same kind keep their parentheses
+        round_trip(&and(vec![a.clone(), and(vec![b.clone(), c.clone()])]));
+        round_trip(&and(vec![and(vec![a.clone(), b.clone()]), c.clone()]));
+        round_trip(&or(vec![a.clone(), or(vec![b.clone(), c.clone()])]));
+        round_trip(&and(vec![a.clone(), or(vec![b.clone(), c.clone()])]));
+        round_trip(&or(vec![a.clone(), and(vec![b.clone(), c.clone()])]));
+        round_trip(&and(vec![a.clone(), b.clone(), c.clone()]));
+        // single operands are written as `and(...)` and `or(...)`
+        round_trip(&and(vec![a.clone()]));
+        round_trip(&or(vec![a.clone()]));
+        round_trip(&and(vec![or(vec![a.clone(), b.clone()])]));
+        round_trip(&!or(vec![and(vec![a.clone()])]));
+        round_trip(&(and(vec![a]) & or(vec![b]) | c));
+        let f: LinkFilter = "or(key:and(\"a\"))".parse().unwrap();
+        assert_eq!(f.to_string(), r#"or(key(and(text("a"))))"#);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex() {
+        round_trip(&DataFilter::text(TextFilter::regex(r"^\d+").unwrap()));
+
+        let e = parse(r#"text(regex("("))"#).unwrap_err();
+        assert_eq!(e.span(), 11..14);
+        assert!(matches!(e.kind(), ParseErrorKind::InvalidRegex(_)));
+    }
+
+    #[test]
+    fn link() {
+        let f: LinkFilter = "key:\"a\" | !target(any)".parse().unwrap();
+        assert_eq!(f.to_string(), r#"key(text("a")) | !target(any)"#);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |s: &str| {
+            let e = parse(s).unwrap_err();
+            (e.kind().clone(), e.span())
+        };
+
+        assert_eq!(
+            error("text(\"foo"),
+            (ParseErrorKind::UnterminatedString, 5..9)
+        );
+        assert_eq!(error(r#""\q""#), (ParseErrorKind::InvalidEscape, 1..3));
+        assert_eq!(
+            error("unique ?"),
+            (ParseErrorKind::UnexpectedChar('?'), 7..8)
+        );
+        assert_eq!(
+            error("foo"),
+            (ParseErrorKind::UnknownName("foo".to_owned()), 0..3)
+        );
+        assert_eq!(error("int(1.5..)"), (ParseErrorKind::InvalidNumber, 4..7));
+        assert_eq!(error("id:xyz"), (ParseErrorKind::InvalidId, 3..6));
+        assert_eq!(
+            error("(any"),
+            (
+                ParseErrorKind::Unexpected {
+                    expected: "`)`",
+                    found: "end of input".to_owned()
+                },
+                4..4
+            )
+        );
+        assert_eq!(
+            error("any &"),
+            (
+                ParseErrorKind::Unexpected {
+                    expected: "filter",
+                    found: "end of input".to_owned()
+                },
+                5..5
+            )
+        );
+        assert_eq!(
+            parse("any )").unwrap_err().to_string(),
+            "expected end of input, found `)` at 4..5"
+        );
+    }
+
+    #[test]
+    fn too_deep() {
+        let deep =
+            |open: &str, close: &str| format!("{}any{}", open.repeat(10_000), close.repeat(10_000));
+        let e = parse(&deep("(", ")")).unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::TooDeep);
+        assert_eq!(e.span(), MAX_DEPTH..MAX_DEPTH + 1);
+        let e = parse(&deep("!", "")).unwrap_err();
+        assert_eq!(e.span(), MAX_DEPTH..MAX_DEPTH + 1);
+        let e = parse(&deep("not ", "")).unwrap_err();
+        assert_eq!(e.span(), 4 * MAX_DEPTH..4 * MAX_DEPTH + 3);
+        let e = parse(&deep("linked(key(", "))")).unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::TooDeep);
+        let e = parse(&format!(
+            "text({}\"a\"{})",
+            "ignore_case(".repeat(10_000),
+            ")".repeat(10_000)
+        ))
+        .unwrap_err();
+        assert_eq!(e.kind(), &ParseErrorKind::TooDeep);
+
+        let f = parse(&format!(
+            "{}any{}",
+            "(".repeat(MAX_DEPTH),
+            ")".repeat(MAX_DEPTH)
+        ));
+        assert_eq!(f, Ok(DataFilter::Any));
+    }
+}
@@ -0,0 +1,159 @@
+use std::ops::Range;
+
+use super::{ParseError, ParseErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token<'a> {
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Bang,
+    Amp,
+    Pipe,
+    Lt,
+    Eq,
+    DotDot,
+    Str(String),
+    Word(&'a str),
+    End,
+}
+
+impl Token<'_> {
+    /// Describes the token for error messages.
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Token::LParen => "`(`".to_owned(),
+            Token::RParen => "`)`".to_owned(),
+            Token::Comma => "`,`".to_owned(),
+            Token::Colon => "`:`".to_owned(),
+            Token::Bang => "`!`".to_owned(),
+            Token::Amp => "`&`".to_owned(),
+            Token::Pipe => "`|`".to_owned(),
+            Token::Lt => "`<`".to_owned(),
+            Token::Eq => "`=`".to_owned(),
+            Token::DotDot => "`..`".to_owned(),
+            Token::Str(s) => format!("string {s:?}"),
+            Token::Word(w) => format!("`{w}`"),
+            Token::End => "end of input".to_owned(),
+        }
+    }
+}
+
+/// Splits `input` into tokens, ending with [`Token::End`].
+pub(super) fn tokenize(input: &str) -> Result<Vec<(Token<'_>, Range<usize>)>, ParseError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'(' => Token::LParen,
+            b')' => Token::RParen,
+            b',' => Token::Comma,
+            b':' => Token::Colon,
+            b'!' => Token::Bang,
+            b'&' => Token::Amp,
+            b'|' => Token::Pipe,
+            b'<' => Token::Lt,
+            b'=' => Token::Eq,
+            b'.' if bytes.get(i + 1) == Some(&b'.') => {
+                i += 1;
+                Token::DotDot
+            }
+            b'"' => {
+                let (s, end) = string(input, i)?;
+                i = end;
+                tokens.push((Token::Str(s), start..i));
+                continue;
+            }
+            b if is_word(b) => {
+                while i < bytes.len()
+                    && (is_word(bytes[i])
+                        || bytes[i] == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+                {
+                    i += 1;
+                }
+                tokens.push((Token::Word(&input[start..i]), start..i));
+                continue;
+            }
+            _ => {
+                let c = input[i..].chars().next().unwrap_or_default();
+                return Err(ParseError::new(
+                    ParseErrorKind::UnexpectedChar(c),
+                    i..i + c.len_utf8(),
+                ));
+            }
+        };
+        i += 1;
+        tokens.push((token, start..i));
+    }
+    tokens.push((Token::End, input.len()..input.len()));
+    Ok(tokens)
+}
+
+#[inline]
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'+')
+}
+
+/// Reads the string literal starting at `start`, returning its unescaped
+/// contents and the offset after the closing quote.
+///
+/// Supports the escapes `str`'s `Debug` impl produces.
+fn string(input: &str, start: usize) -> Result<(String, usize), ParseError> {
+    let mut s = String::new();
+    let mut chars = input[start + 1..]
+        .char_indices()
+        .map(|(i, c)| (start + 1 + i, c));
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((s, i + 1)),
+            '\\' => {
+                let invalid = |end: usize| ParseError::new(ParseErrorKind::InvalidEscape, i..end);
+                let Some((j, e)) = chars.next() else {
+                    return Err(invalid(i + 1));
+                };
+                s.push(match e {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    '0' => '\0',
+                    '\\' | '"' | '\'' => e,
+                    'u' => {
+                        let Some((_, '{')) = chars.next() else {
+                            return Err(invalid(j + 1));
+                        };
+                        let mut code = 0u32;
+                        let mut end = j + 2;
+                        loop {
+                            match chars.next() {
+                                Some((k, '}')) => {
+                                    end = k + 1;
+                                    break;
+                                }
+                                Some((k, h)) if h.is_ascii_hexdigit() && code < 0x10_0000 => {
+                                    code = code * 16 + h.to_digit(16).unwrap_or_default();
+                                    end = k + 1;
+                                }
+                                Some((k, _)) => return Err(invalid(k + 1)),
+                                None => return Err(invalid(end)),
+                            }
+                        }
+                        char::from_u32(code).ok_or_else(|| invalid(end))?
+                    }
+                    _ => return Err(invalid(j + e.len_utf8())),
+                });
+            }
+            c => s.push(c),
+        }
+    }
+    Err(ParseError::new(
+        ParseErrorKind::UnterminatedString,
+        start..input.len(),
+    ))
+}
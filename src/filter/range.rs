@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+use super::{Cost, Filter, Infix, Optimizable, TruthyDefault};
+
+/// Matches values between a start and an end bound.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    start: Bound<T>,
+    end: Bound<T>,
+}
+
+impl<T> Range<T> {
+    #[inline]
+    #[must_use]
+    pub const fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        Self { start, end }
+    }
+    /// Matches all values.
+    #[inline]
+    #[must_use]
+    pub const fn full() -> Self {
+        Self::new(Bound::Unbounded, Bound::Unbounded)
+    }
+    /// Matches values less than `value`.
+    #[inline]
+    #[must_use]
+    pub const fn lt(value: T) -> Self {
+        Self::new(Bound::Unbounded, Bound::Excluded(value))
+    }
+    /// Matches values less than or equal to `value`.
+    #[inline]
+    #[must_use]
+    pub const fn le(value: T) -> Self {
+        Self::new(Bound::Unbounded, Bound::Included(value))
+    }
+    /// Matches values greater than `value`.
+    #[inline]
+    #[must_use]
+    pub const fn gt(value: T) -> Self {
+        Self::new(Bound::Excluded(value), Bound::Unbounded)
+    }
+    /// Matches values greater than or equal to `value`.
+    #[inline]
+    #[must_use]
+    pub const fn ge(value: T) -> Self {
+        Self::new(Bound::Included(value), Bound::Unbounded)
+    }
+    /// Matches values from `start` to `end`, both inclusive.
+    #[inline]
+    #[must_use]
+    pub const fn between(start: T, end: T) -> Self {
+        Self::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl<T> RangeBounds<T> for Range<T> {
+    #[inline]
+    fn start_bound(&self) -> Bound<&T> {
+        self.start.as_ref()
+    }
+    #[inline]
+    fn end_bound(&self) -> Bound<&T> {
+        self.end.as_ref()
+    }
+}
+
+impl<T: PartialOrd> Filter<T> for Range<T> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.contains(obj)
+    }
+}
+
+impl<T: PartialOrd> Optimizable for Range<T> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        use Bound::*;
+        let (start, end) = match (&self.start, &self.end) {
+            (Unbounded, Unbounded) => return Some(true),
+            (Included(v) | Excluded(v), Unbounded) | (Unbounded, Included(v) | Excluded(v)) => {
+                // a bound like NaN can't be compared with anything
+                return v.partial_cmp(v).is_none().then_some(false);
+            }
+            (Included(start) | Excluded(start), Included(end) | Excluded(end)) => (start, end),
+        };
+        let empty = match start.partial_cmp(end) {
+            None | Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => {
+                !matches!((&self.start, &self.end), (Included(_), Included(_)))
+            }
+            Some(Ordering::Less) => false,
+        };
+        empty.then_some(false)
+    }
+}
+
+impl<T: PartialOrd> Cost for Range<T> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        match (&self.start, &self.end) {
+            (Bound::Unbounded, Bound::Unbounded) => 0.0,
+            (Bound::Unbounded, _) | (_, Bound::Unbounded) => 1.0,
+            _ => 2.0,
+        }
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        match self.as_bool() {
+            Some(b) => f64::from(u8::from(b)),
+            None => 0.5,
+        }
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Range<T> {
+    /// Writes the range like Rust range syntax, with an excluded start
+    /// written as `1<..`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.start {
+            Bound::Included(start) => write!(f, "{start}..")?,
+            Bound::Excluded(start) => write!(f, "{start}<..")?,
+            Bound::Unbounded => f.write_str("..")?,
+        }
+        match &self.end {
+            Bound::Included(end) => write!(f, "={end}"),
+            Bound::Excluded(end) => write!(f, "{end}"),
+            Bound::Unbounded => Ok(()),
+        }
+    }
+}
+
+impl<T: std::fmt::Display> Infix for Range<T> {}
+
+/// Serialized as a map of the comparisons bounding the range, like
+/// `{"ge":1,"lt":5}`, with `{}` being the full range.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RangeRepr<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gt: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ge: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lt: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    le: Option<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Range<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (gt, ge) = match &self.start {
+            Bound::Excluded(start) => (Some(start), None),
+            Bound::Included(start) => (None, Some(start)),
+            Bound::Unbounded => (None, None),
+        };
+        let (lt, le) = match &self.end {
+            Bound::Excluded(end) => (Some(end), None),
+            Bound::Included(end) => (None, Some(end)),
+            Bound::Unbounded => (None, None),
+        };
+        RangeRepr { gt, ge, lt, le }.serialize(serializer)
+    }
+}
+
+/// Serializes a float range, failing for infinite and NaN bounds, as JSON
+/// would turn them into `null`.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_finite<S: serde::Serializer>(
+    range: &Range<f64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let finite = |bound: &Bound<f64>| match bound {
+        Bound::Included(value) | Bound::Excluded(value) => value.is_finite(),
+        Bound::Unbounded => true,
+    };
+    if !finite(&range.start) || !finite(&range.end) {
+        return Err(serde::ser::Error::custom(
+            "range bounds must be finite to serialize",
+        ));
+    }
+    serde::Serialize::serialize(range, serializer)
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Range<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let repr = RangeRepr::deserialize(deserializer)?;
+        let start = match (repr.gt, repr.ge) {
+            (Some(_), Some(_)) => return Err(D::Error::custom("both `gt` and `ge` given")),
+            (Some(start), None) => Bound::Excluded(start),
+            (None, Some(start)) => Bound::Included(start),
+            (None, None) => Bound::Unbounded,
+        };
+        let end = match (repr.lt, repr.le) {
+            (Some(_), Some(_)) => return Err(D::Error::custom("both `lt` and `le` given")),
+            (Some(end), None) => Bound::Excluded(end),
+            (None, Some(end)) => Bound::Included(end),
+            (None, None) => Bound::Unbounded,
+        };
+        Ok(Self::new(start, end))
+    }
+}
+
+impl<T> TruthyDefault for Range<T> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self::full()
+    }
+}
+
+impl<T> From<std::ops::Range<T>> for Range<T> {
+    #[inline]
+    fn from(value: std::ops::Range<T>) -> Self {
+        Self::new(Bound::Included(value.start), Bound::Excluded(value.end))
+    }
+}
+
+impl<T> From<std::ops::RangeInclusive<T>> for Range<T> {
+    #[inline]
+    fn from(value: std::ops::RangeInclusive<T>) -> Self {
+        let (start, end) = value.into_inner();
+        Self::between(start, end)
+    }
+}
+
+impl<T> From<std::ops::RangeFrom<T>> for Range<T> {
+    #[inline]
+    fn from(value: std::ops::RangeFrom<T>) -> Self {
+        Self::ge(value.start)
+    }
+}
+
+impl<T> From<std::ops::RangeTo<T>> for Range<T> {
+    #[inline]
+    fn from(value: std::ops::RangeTo<T>) -> Self {
+        Self::lt(value.end)
+    }
+}
+
+impl<T> From<std::ops::RangeToInclusive<T>> for Range<T> {
+    #[inline]
+    fn from(value: std::ops::RangeToInclusive<T>) -> Self {
+        Self::le(value.end)
+    }
+}
+
+impl<T> From<std::ops::RangeFull> for Range<T> {
+    #[inline]
+    fn from(_: std::ops::RangeFull) -> Self {
+        Self::full()
+    }
+}
+
+impl<T> From<(Bound<T>, Bound<T>)> for Range<T> {
+    #[inline]
+    fn from((start, end): (Bound<T>, Bound<T>)) -> Self {
+        Self::new(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert!(Range::lt(3).matches(&2));
+        assert!(!Range::lt(3).matches(&3));
+        assert!(Range::le(3).matches(&3));
+        assert!(Range::gt(3).matches(&4));
+        assert!(!Range::gt(3).matches(&3));
+        assert!(Range::ge(3).matches(&3));
+        assert!(Range::between(1, 3).matches(&1));
+        assert!(Range::between(1, 3).matches(&3));
+        assert!(!Range::between(1, 3).matches(&4));
+        assert!(Range::from(1..3).matches(&1));
+        assert!(!Range::from(1..3).matches(&3));
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(Range::<i32>::full().as_bool(), Some(true));
+        assert!(Range::from(..).matches_owned(i32::MIN));
+        assert_eq!(Range::between(1, 1).as_bool(), None);
+        assert_eq!(Range::from(1..1).as_bool(), Some(false));
+        assert_eq!(Range::between(3, 1).as_bool(), Some(false));
+        assert_eq!(Range::from(1..3).as_bool(), None);
+        assert_eq!(Range::lt(f64::NAN).as_bool(), Some(false));
+        assert_eq!(Range::between(0.0, f64::NAN).as_bool(), Some(false));
+        assert_eq!(Range::lt(1.0).as_bool(), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Range::<i32>::full().to_string(), "..");
+        assert_eq!(Range::from(1..3).to_string(), "1..3");
+        assert_eq!(Range::between(1, 3).to_string(), "1..=3");
+        assert_eq!(Range::gt(-1.5).to_string(), "-1.5<..");
+        assert_eq!(Range::le(3).to_string(), "..=3");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let json = serde_json::to_string(&Range::from(1..5)).unwrap();
+        assert_eq!(json, r#"{"ge":1,"lt":5}"#);
+        let json = serde_json::to_string(&Range::<i32>::full()).unwrap();
+        assert_eq!(json, "{}");
+
+        let r: Range<i32> = serde_json::from_str(r#"{"gt":1,"le":5}"#).unwrap();
+        assert_eq!(r.to_string(), "1<..=5");
+        assert!(serde_json::from_str::<Range<i32>>(r#"{"gt":1,"ge":5}"#).is_err());
+        assert!(serde_json::from_str::<Range<i32>>(r#"{"eq":1}"#).is_err());
+
+        let json = serde_json::to_string(&Range::between(-1.5, 2.0)).unwrap();
+        assert_eq!(json, r#"{"ge":-1.5,"le":2.0}"#);
+    }
+}
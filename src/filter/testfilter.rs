@@ -1,6 +1,8 @@
-use super::{FalsyDefault, Filter, Optimizable, TruthyDefault};
+use super::{
+    Cost, Explain, Explanation, FalsyDefault, Filter, Optimizable, TriFilter, Truth, TruthyDefault,
+};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TestFilter {
     Any,
     None,
@@ -17,6 +19,40 @@ impl<T> Filter<T> for TestFilter {
     }
 }
 
+impl<T> TriFilter<T> for TestFilter {
+    fn eval(&self, _: &T) -> Truth {
+        match self {
+            TestFilter::Any => Truth::True,
+            TestFilter::None => Truth::False,
+            TestFilter::Panic => Truth::Unknown,
+        }
+    }
+}
+
+impl<T> Explain<T> for TestFilter {
+    fn explain(&self, obj: &T) -> Explanation {
+        Explanation::new(self.matches(obj), Explain::<T>::describe(self))
+    }
+
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl Cost for TestFilter {
+    fn cost(&self) -> f64 {
+        1.0
+    }
+
+    fn selectivity(&self) -> f64 {
+        match self {
+            TestFilter::Any => 1.0,
+            TestFilter::None => 0.0,
+            TestFilter::Panic => 0.5,
+        }
+    }
+}
+
 impl Optimizable for TestFilter {
     fn as_bool(&self) -> Option<bool> {
         match self {
@@ -1,32 +1,396 @@
-use super::{Filter, Optimizable};
+use super::{Cost, Explain, Explanation, Filter, Infix, Optimizable};
 
-#[derive(Debug)]
+mod glob;
+use glob::Glob;
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[derive(Debug, Clone)]
 pub struct TextFilter {
     search: Box<str>,
+    mode: Mode,
+    case: Case,
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Exact,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Glob(Glob),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+/// A compiled regex, remembering the string it matches if the pattern is an
+/// anchored literal like `^foo$`.
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+struct Regex {
+    regex: regex::Regex,
+    literal: Option<Box<str>>,
+}
+
+#[cfg(feature = "regex")]
+impl Regex {
+    #[inline]
+    fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+/// A pattern passed to [`TextFilter::regex`] isn't a valid regex.
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexError {
+    message: String,
+}
+
+#[cfg(feature = "regex")]
+impl std::fmt::Display for RegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl std::error::Error for RegexError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Case {
+    Sensitive,
+    AsciiInsensitive,
+    Insensitive,
 }
 
 impl TextFilter {
+    #[inline]
+    fn new(search: impl Into<String>, mode: Mode) -> Self {
+        Self {
+            search: search.into().into_boxed_str(),
+            mode,
+            case: Case::Sensitive,
+        }
+    }
+
+    /// Matches strings equal to `search`.
+    #[inline]
+    #[must_use]
+    pub fn equals(search: impl Into<String>) -> Self {
+        Self::new(search, Mode::Exact)
+    }
+
+    /// Matches strings containing `search`.
+    #[inline]
+    #[must_use]
+    pub fn contains(search: impl Into<String>) -> Self {
+        Self::new(search, Mode::Contains)
+    }
+
+    /// Matches strings starting with `search`.
+    #[inline]
+    #[must_use]
+    pub fn starts_with(search: impl Into<String>) -> Self {
+        Self::new(search, Mode::StartsWith)
+    }
+
+    /// Matches strings ending with `search`.
+    #[inline]
+    #[must_use]
+    pub fn ends_with(search: impl Into<String>) -> Self {
+        Self::new(search, Mode::EndsWith)
+    }
+
+    /// Matches strings against a shell-style wildcard pattern supporting `*`,
+    /// `?`, `[...]` classes and `\` escapes.
+    ///
+    /// Patterns whose only wildcards are a leading or trailing `*` are turned
+    /// into the equivalent equality, prefix, suffix or substring filter.
+    /// The case modifiers fold the case of literals and classes alike.
+    #[must_use]
+    pub fn glob(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        let glob = Glob::new(&pattern);
+        match glob.as_literal() {
+            Some((false, literal, false)) => Self::equals(literal),
+            Some((false, literal, true)) => Self::starts_with(literal),
+            Some((true, literal, false)) => Self::ends_with(literal),
+            Some((true, literal, true)) => Self::contains(literal),
+            None => Self::new(pattern, Mode::Glob(glob)),
+        }
+    }
+
+    /// Matches strings in which the regular expression `pattern` finds a match.
+    ///
+    /// If the pattern is an anchored literal like `^foo$`,
+    /// [`TextFilter::exact`] returns that literal.
+    /// The case modifiers have no effect on regex filters, use `(?i)` instead.
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: impl Into<String>) -> Result<Self, RegexError> {
+        let pattern = pattern.into();
+        let regex = regex::Regex::new(&pattern).map_err(|e| RegexError {
+            message: e.to_string(),
+        })?;
+        let literal = anchored_literal(&pattern).map(String::into_boxed_str);
+        Ok(Self::new(pattern, Mode::Regex(Regex { regex, literal })))
+    }
+
+    /// Ignore ASCII case when matching.
+    #[inline]
+    #[must_use]
+    pub fn ignore_ascii_case(mut self) -> Self {
+        match self.mode {
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => return self,
+            // globs fold while matching, folding the pattern could turn a
+            // class range inside out
+            Mode::Glob(_) => {}
+            _ => self.search = self.search.to_ascii_lowercase().into_boxed_str(),
+        }
+        self.case = Case::AsciiInsensitive;
+        self
+    }
+
+    /// Ignore case when matching, using Unicode lowercase mapping.
+    #[inline]
+    #[must_use]
+    pub fn ignore_case(mut self) -> Self {
+        match self.mode {
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => return self,
+            Mode::Glob(_) => {}
+            _ => self.search = lowercase(&self.search).collect::<String>().into_boxed_str(),
+        }
+        self.case = Case::Insensitive;
+        self
+    }
+
     /// If the filter matches exactly one string, return that string.
     pub fn exact(&self) -> Option<&str> {
-        Some(&self.search)
+        match (&self.mode, self.case) {
+            (Mode::Exact, Case::Sensitive) => Some(&self.search),
+            #[cfg(feature = "regex")]
+            (Mode::Regex(regex), _) => regex.literal.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// If the filter matches all strings containing one string, return that string.
+    pub fn substring(&self) -> Option<&str> {
+        match (&self.mode, self.case) {
+            (Mode::Contains, Case::Sensitive) => Some(&self.search),
+            _ => None,
+        }
     }
 }
 
+/// Returns the literal a pattern like `^foo$` matches exactly, if any.
+#[cfg(feature = "regex")]
+fn anchored_literal(pattern: &str) -> Option<String> {
+    use regex_syntax::hir::{HirKind, Look};
+
+    let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+    let HirKind::Concat(parts) = hir.kind() else {
+        return None;
+    };
+    let [first, literals @ .., last] = parts.as_slice() else {
+        return None;
+    };
+    if !matches!(first.kind(), HirKind::Look(Look::Start))
+        || !matches!(last.kind(), HirKind::Look(Look::End))
+    {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    for literal in literals {
+        match literal.kind() {
+            HirKind::Literal(literal) => bytes.extend_from_slice(&literal.0),
+            _ => return None,
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[inline]
+fn lowercase(s: &str) -> impl DoubleEndedIterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
 impl Filter<str> for TextFilter {
     #[inline]
     fn matches(&self, obj: &str) -> bool {
-        self.search.as_ref() == obj
+        let search = self.search.as_ref();
+        match (&self.mode, self.case) {
+            (Mode::Glob(glob), case) => glob.matches(obj, case),
+            #[cfg(feature = "regex")]
+            (Mode::Regex(regex), _) => regex.is_match(obj),
+            (Mode::Exact, Case::Sensitive) => search == obj,
+            (Mode::Contains, Case::Sensitive) => obj.contains(search),
+            (Mode::StartsWith, Case::Sensitive) => obj.starts_with(search),
+            (Mode::EndsWith, Case::Sensitive) => obj.ends_with(search),
+            (Mode::Exact, Case::AsciiInsensitive) => obj.eq_ignore_ascii_case(search),
+            (Mode::Contains, Case::AsciiInsensitive) => {
+                search.is_empty()
+                    || obj
+                        .as_bytes()
+                        .windows(search.len())
+                        .any(|w| w.eq_ignore_ascii_case(search.as_bytes()))
+            }
+            (Mode::StartsWith, Case::AsciiInsensitive) => obj
+                .as_bytes()
+                .get(..search.len())
+                .is_some_and(|b| b.eq_ignore_ascii_case(search.as_bytes())),
+            (Mode::EndsWith, Case::AsciiInsensitive) => obj
+                .len()
+                .checked_sub(search.len())
+                .is_some_and(|i| obj.as_bytes()[i..].eq_ignore_ascii_case(search.as_bytes())),
+            (Mode::Exact, Case::Insensitive) => lowercase(obj).eq(search.chars()),
+            (Mode::Contains, Case::Insensitive) => {
+                lowercase(obj).collect::<String>().contains(search)
+            }
+            (Mode::StartsWith, Case::Insensitive) => {
+                let mut obj = lowercase(obj);
+                search.chars().all(|c| obj.next() == Some(c))
+            }
+            (Mode::EndsWith, Case::Insensitive) => {
+                let mut obj = obj.chars().rev().flat_map(|c| c.to_lowercase().rev());
+                search.chars().rev().all(|c| obj.next() == Some(c))
+            }
+        }
+    }
+}
+
+impl TextFilter {
+    /// Returns how the filter compares, affirmed and negated.
+    fn verbs(&self) -> (&'static str, &'static str) {
+        match self.mode {
+            Mode::Exact => ("equals", "does not equal"),
+            Mode::Contains => ("contains", "does not contain"),
+            Mode::StartsWith => ("starts with", "does not start with"),
+            Mode::EndsWith => ("ends with", "does not end with"),
+            Mode::Glob(_) => ("matches glob", "does not match glob"),
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => ("matches regex", "does not match regex"),
+        }
+    }
+
+    fn case_suffix(&self) -> &'static str {
+        match self.case {
+            Case::Sensitive => "",
+            Case::AsciiInsensitive => " (ignoring ASCII case)",
+            Case::Insensitive => " (ignoring case)",
+        }
+    }
+}
+
+impl Explain<str> for TextFilter {
+    fn explain(&self, obj: &str) -> Explanation {
+        let matched = self.matches(obj);
+        let (verb, negated) = self.verbs();
+        let verb = if matched { verb } else { negated };
+        let detail = format!("{obj:?} {verb} {:?}{}", self.search, self.case_suffix());
+        Explanation::new(matched, detail)
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {:?}{}", self.verbs().0, self.search, self.case_suffix())
+    }
+}
+
+impl std::fmt::Display for TextFilter {
+    /// Writes the filter as `"foo"`, `contains("foo")`, `glob("*.rs")` and so
+    /// on, wrapped in `ignore_case(...)` or `ignore_ascii_case(...)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let case = match self.case {
+            Case::Sensitive => None,
+            Case::AsciiInsensitive => Some("ignore_ascii_case"),
+            Case::Insensitive => Some("ignore_case"),
+        };
+        let mode = match self.mode {
+            Mode::Exact => None,
+            Mode::Contains => Some("contains"),
+            Mode::StartsWith => Some("starts_with"),
+            Mode::EndsWith => Some("ends_with"),
+            Mode::Glob(_) => Some("glob"),
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => Some("regex"),
+        };
+        let search = &*self.search;
+        match (case, mode) {
+            (None, None) => write!(f, "{search:?}"),
+            (None, Some(mode)) | (Some(mode), None) => write!(f, "{mode}({search:?})"),
+            (Some(case), Some(mode)) => write!(f, "{case}({mode}({search:?}))"),
+        }
+    }
+}
+
+impl Infix for TextFilter {}
+
+impl Cost for TextFilter {
+    #[inline]
+    fn cost(&self) -> f64 {
+        let cost = match self.mode {
+            Mode::Exact | Mode::StartsWith | Mode::EndsWith => 1.0,
+            Mode::Contains => 4.0,
+            Mode::Glob(_) => 8.0,
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => 16.0,
+        };
+        match self.case {
+            Case::Sensitive => cost,
+            // folding the case allocates
+            Case::AsciiInsensitive | Case::Insensitive => cost * 2.0,
+        }
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        match self.mode {
+            Mode::Exact => 0.1,
+            _ => 0.5,
+        }
+    }
+}
+
+impl Optimizable for TextFilter {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        match self.mode {
+            Mode::Contains | Mode::StartsWith | Mode::EndsWith if self.search.is_empty() => {
+                Some(true)
+            }
+            _ => None,
+        }
     }
 }
 
-impl Optimizable for TextFilter {}
+/// Filters are equal if they compare the same way against the same search
+/// string or pattern.
+impl PartialEq for TextFilter {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(&self.mode) == std::mem::discriminant(&other.mode)
+            && self.case == other.case
+            && self.search == other.search
+    }
+}
+
+impl Eq for TextFilter {}
+
+impl std::hash::Hash for TextFilter {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.mode).hash(state);
+        self.case.hash(state);
+        self.search.hash(state);
+    }
+}
 
 impl From<String> for TextFilter {
     #[inline]
     fn from(value: String) -> Self {
-        Self {
-            search: value.into_boxed_str(),
-        }
+        Self::equals(value)
     }
 }
 
@@ -36,3 +400,187 @@ impl From<&str> for TextFilter {
         Self::from(value.to_owned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equals() {
+        let f = TextFilter::from("foo");
+
+        assert!(f.matches("foo"));
+        assert!(!f.matches("Foo"));
+        assert!(!f.matches("foobar"));
+        assert_eq!(f.exact(), Some("foo"));
+    }
+
+    #[test]
+    fn contains() {
+        let f = TextFilter::contains("oba");
+
+        assert!(f.matches("foobar"));
+        assert!(!f.matches("fooBar"));
+        assert_eq!(f.exact(), None);
+        assert_eq!(TextFilter::contains("").as_bool(), Some(true));
+    }
+
+    #[test]
+    fn starts_with() {
+        let f = TextFilter::starts_with("foo");
+
+        assert!(f.matches("foobar"));
+        assert!(!f.matches("barfoo"));
+        assert_eq!(f.exact(), None);
+    }
+
+    #[test]
+    fn ends_with() {
+        let f = TextFilter::ends_with("bar");
+
+        assert!(f.matches("foobar"));
+        assert!(!f.matches("barfoo"));
+        assert_eq!(f.exact(), None);
+    }
+
+    #[test]
+    fn glob() {
+        let f = TextFilter::glob("[abc]?.rs");
+
+        assert!(f.matches("a1.rs"));
+        assert!(!f.matches("d1.rs"));
+        assert_eq!(f.exact(), None);
+        assert_eq!(f.as_bool(), None);
+
+        assert_eq!(TextFilter::glob("*").as_bool(), Some(true));
+        assert_eq!(TextFilter::glob(r"foo\?").exact(), Some("foo?"));
+        assert!(TextFilter::glob("*.rs").matches("lib.rs"));
+        assert!(TextFilter::glob("foo*").matches("foobar"));
+        assert!(TextFilter::glob("*oba*").matches("foobar"));
+        assert!(!TextFilter::glob("*oba*").matches("foo"));
+    }
+
+    #[test]
+    fn glob_case() {
+        // rewritten and real globs fold the case the same way
+        assert!(TextFilter::glob("FO*").ignore_case().matches("foobar"));
+        assert!(TextFilter::glob("F?O*").ignore_case().matches("foobar"));
+        assert!(TextFilter::glob("F?O*")
+            .ignore_ascii_case()
+            .matches("foobar"));
+        assert!(!TextFilter::glob("Ä?*").ignore_ascii_case().matches("ä_"));
+        assert!(TextFilter::glob("Ä?*").ignore_case().matches("ä_"));
+        assert!(TextFilter::glob("[A-C]?").ignore_case().matches("b_"));
+        assert!(!TextFilter::glob("F?O*").matches("foobar"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex() {
+        let f = TextFilter::regex("^fo+bar").unwrap();
+
+        assert!(f.matches("fooobar"));
+        assert!(!f.matches("xfoobar"));
+        assert_eq!(f.exact(), None);
+
+        let e = TextFilter::regex("(").unwrap_err();
+        assert!(e.to_string().contains("unclosed group"), "{e}");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_literal() {
+        assert_eq!(TextFilter::regex("^foo$").unwrap().exact(), Some("foo"));
+        assert_eq!(TextFilter::regex(r"\Af\.o\z").unwrap().exact(), Some("f.o"));
+        assert_eq!(TextFilter::regex("^$").unwrap().exact(), Some(""));
+        assert_eq!(TextFilter::regex("foo").unwrap().exact(), None);
+        assert_eq!(TextFilter::regex("^fo?$").unwrap().exact(), None);
+        assert_eq!(TextFilter::regex("(?m)^foo$").unwrap().exact(), None);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_case() {
+        // anchored literals ignore the case modifiers like all other regexes
+        for pattern in ["^foo$", "^fo+$"] {
+            let regex = || TextFilter::regex(pattern).unwrap();
+            assert!(!regex().ignore_case().matches("FOO"));
+            assert!(!regex().ignore_ascii_case().matches("FOO"));
+            assert!(regex().ignore_case().matches("foo"));
+        }
+        let f = TextFilter::regex("^foo$").unwrap().ignore_case();
+        assert_eq!(f.exact(), Some("foo"));
+        assert!(TextFilter::regex("(?i)^foo$").unwrap().matches("FOO"));
+    }
+
+    #[test]
+    fn ignore_ascii_case() {
+        assert!(TextFilter::equals("FoO").ignore_ascii_case().matches("fOo"));
+        assert!(TextFilter::contains("OB")
+            .ignore_ascii_case()
+            .matches("foobar"));
+        assert!(TextFilter::starts_with("FOO")
+            .ignore_ascii_case()
+            .matches("foobar"));
+        assert!(TextFilter::ends_with("BAR")
+            .ignore_ascii_case()
+            .matches("foobar"));
+        assert!(!TextFilter::ends_with("BAR")
+            .ignore_ascii_case()
+            .matches("ar"));
+        assert!(!TextFilter::equals("Ä").ignore_ascii_case().matches("ä"));
+        assert_eq!(TextFilter::equals("foo").ignore_ascii_case().exact(), None);
+    }
+
+    #[test]
+    fn explain() {
+        let f = TextFilter::equals("foo");
+        assert_eq!(
+            f.explain("bar").to_string(),
+            "failed: \"bar\" does not equal \"foo\"\n"
+        );
+        assert!(f.explain("foo").matched());
+
+        let f = TextFilter::starts_with("FO").ignore_ascii_case();
+        assert_eq!(f.describe(), "starts with \"fo\" (ignoring ASCII case)");
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(TextFilter::equals("a\"b").to_string(), r#""a\"b""#);
+        assert_eq!(TextFilter::ends_with("b").to_string(), r#"ends_with("b")"#);
+        assert_eq!(TextFilter::glob("*.rs").to_string(), r#"ends_with(".rs")"#);
+        assert_eq!(TextFilter::glob("a?").to_string(), r#"glob("a?")"#);
+        assert_eq!(
+            TextFilter::equals("Foo").ignore_case().to_string(),
+            r#"ignore_case("foo")"#
+        );
+        assert_eq!(
+            TextFilter::contains("Foo").ignore_ascii_case().to_string(),
+            r#"ignore_ascii_case(contains("foo"))"#
+        );
+    }
+
+    #[test]
+    fn eq() {
+        assert_eq!(TextFilter::equals("foo"), TextFilter::from("foo").clone());
+        assert_ne!(TextFilter::equals("foo"), TextFilter::contains("foo"));
+        assert_ne!(
+            TextFilter::equals("foo"),
+            TextFilter::equals("foo").ignore_case()
+        );
+        assert_eq!(TextFilter::glob("a?"), TextFilter::glob("a?"));
+    }
+
+    #[test]
+    fn ignore_case() {
+        assert!(TextFilter::equals("Ä").ignore_case().matches("ä"));
+        assert!(TextFilter::contains("ÖB").ignore_case().matches("fööbar"));
+        assert!(TextFilter::starts_with("FÖÖ")
+            .ignore_case()
+            .matches("fööbar"));
+        assert!(TextFilter::ends_with("BÄR").ignore_case().matches("foobär"));
+        assert!(!TextFilter::ends_with("BÄR").ignore_case().matches("är"));
+        assert_eq!(TextFilter::equals("foo").ignore_case().exact(), None);
+    }
+}
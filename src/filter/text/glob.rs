@@ -0,0 +1,281 @@
+use super::Case;
+
+/// A shell-style wildcard pattern supporting `*`, `?`, `[...]` classes and
+/// `\` escapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    tokens: Box<[Token]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(char),
+    Any,
+    Star,
+    Class {
+        negated: bool,
+        ranges: Box<[(char, char)]>,
+    },
+}
+
+impl Token {
+    #[inline]
+    fn matches(&self, c: char, case: Case) -> bool {
+        match self {
+            Token::Literal(l) => match case {
+                Case::Sensitive => *l == c,
+                Case::AsciiInsensitive => l.eq_ignore_ascii_case(&c),
+                Case::Insensitive => *l == c || l.to_lowercase().eq(c.to_lowercase()),
+            },
+            Token::Any => true,
+            Token::Star => unreachable!("stars are handled by the matcher"),
+            Token::Class { negated, ranges } => {
+                let contains = |c: char| ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi);
+                let folded = match case {
+                    Case::Sensitive => false,
+                    Case::AsciiInsensitive => {
+                        contains(c.to_ascii_lowercase()) || contains(c.to_ascii_uppercase())
+                    }
+                    Case::Insensitive => {
+                        single(c.to_lowercase()).is_some_and(contains)
+                            || single(c.to_uppercase()).is_some_and(contains)
+                    }
+                };
+                (contains(c) || folded) != *negated
+            }
+        }
+    }
+}
+
+/// The only char of a case mapping, if it maps to a single char.
+#[inline]
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+impl Glob {
+    /// Parses a pattern.
+    ///
+    /// Like in shells, an unterminated class or a trailing backslash is
+    /// taken literally, so parsing never fails.
+    pub fn new(pattern: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' if tokens.last() == Some(&Token::Star) => continue,
+                '*' => Token::Star,
+                '?' => Token::Any,
+                '\\' => Token::Literal(chars.next().unwrap_or('\\')),
+                '[' => match parse_class(chars.as_str()) {
+                    Some((token, rest)) => {
+                        chars = rest.chars();
+                        token
+                    }
+                    None => Token::Literal('['),
+                },
+                c => Token::Literal(c),
+            };
+            tokens.push(token);
+        }
+        Self {
+            tokens: tokens.into_boxed_slice(),
+        }
+    }
+
+    /// Splits the pattern into an optional leading `*`, a literal and an
+    /// optional trailing `*`, if it has no other wildcards.
+    pub fn as_literal(&self) -> Option<(bool, String, bool)> {
+        let mut tokens = self.tokens.as_ref();
+        let leading = matches!(tokens.first(), Some(Token::Star));
+        if leading {
+            tokens = &tokens[1..];
+        }
+        let trailing = matches!(tokens.last(), Some(Token::Star));
+        if trailing {
+            tokens = &tokens[..tokens.len() - 1];
+        }
+        let literal = tokens
+            .iter()
+            .map(|t| match t {
+                Token::Literal(c) => Some(*c),
+                _ => None,
+            })
+            .collect::<Option<String>>()?;
+        Some((leading, literal, trailing))
+    }
+
+    /// Whether the pattern matches all of `text`, folding the case of both
+    /// as `case` says.
+    pub fn matches(&self, text: &str, case: Case) -> bool {
+        let tokens = self.tokens.as_ref();
+        let (mut t, mut s) = (0, 0);
+        // token after the last star and the text position it was tried at
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        loop {
+            match tokens.get(t) {
+                Some(Token::Star) => {
+                    t += 1;
+                    backtrack = Some((t, s));
+                    continue;
+                }
+                Some(token) => {
+                    if let Some(c) = text[s..].chars().next() {
+                        if token.matches(c, case) {
+                            t += 1;
+                            s += c.len_utf8();
+                            continue;
+                        }
+                    }
+                }
+                None if s == text.len() => return true,
+                None => {}
+            }
+
+            // mismatch, let the last star consume one more char
+            let Some((bt, bs)) = backtrack else {
+                return false;
+            };
+            let Some(c) = text[bs..].chars().next() else {
+                return false;
+            };
+            t = bt;
+            s = bs + c.len_utf8();
+            backtrack = Some((t, s));
+        }
+    }
+}
+
+/// Parses the class following a `[`, returning it and the remaining pattern.
+fn parse_class(pattern: &str) -> Option<(Token, &str)> {
+    let mut chars = pattern.chars();
+    let mut negated = false;
+    let mut ranges = Vec::new();
+    let mut first = true;
+
+    let mut peek = chars.clone();
+    if let Some('!' | '^') = peek.next() {
+        negated = true;
+        chars = peek;
+    }
+
+    loop {
+        let lo = match chars.next()? {
+            ']' if !first => break,
+            '\\' => chars.next()?,
+            c => c,
+        };
+        first = false;
+
+        let mut peek = chars.clone();
+        let hi = match (peek.next(), peek.next()) {
+            (Some('-'), Some(']')) | (Some('-'), None) => lo,
+            (Some('-'), Some('\\')) => {
+                let hi = peek.next()?;
+                chars = peek;
+                hi
+            }
+            (Some('-'), Some(hi)) => {
+                chars = peek;
+                hi
+            }
+            _ => lo,
+        };
+        ranges.push((lo, hi));
+    }
+
+    let token = Token::Class {
+        negated,
+        ranges: ranges.into_boxed_slice(),
+    };
+    Some((token, chars.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        let g = Glob::new("*.rs");
+        assert!(g.matches("lib.rs", Case::Sensitive));
+        assert!(g.matches(".rs", Case::Sensitive));
+        assert!(!g.matches("lib.rs.bak", Case::Sensitive));
+
+        let g = Glob::new("foo?bar");
+        assert!(g.matches("foo_bar", Case::Sensitive));
+        assert!(g.matches("fooäbar", Case::Sensitive));
+        assert!(!g.matches("foobar", Case::Sensitive));
+
+        let g = Glob::new("a*b*c");
+        assert!(g.matches("abc", Case::Sensitive));
+        assert!(g.matches("axxbyybzc", Case::Sensitive));
+        assert!(!g.matches("axxbyy", Case::Sensitive));
+    }
+
+    #[test]
+    fn classes() {
+        let g = Glob::new("[abc]*");
+        assert!(g.matches("apple", Case::Sensitive));
+        assert!(!g.matches("dog", Case::Sensitive));
+
+        let g = Glob::new("[!a-c]");
+        assert!(g.matches("d", Case::Sensitive));
+        assert!(!g.matches("b", Case::Sensitive));
+
+        let g = Glob::new("[]-]");
+        assert!(g.matches("]", Case::Sensitive));
+        assert!(g.matches("-", Case::Sensitive));
+        assert!(!g.matches("a", Case::Sensitive));
+
+        let g = Glob::new("[a-]");
+        assert!(g.matches("a", Case::Sensitive));
+        assert!(g.matches("-", Case::Sensitive));
+    }
+
+    #[test]
+    fn escapes() {
+        let g = Glob::new(r"\*[\]]\?");
+        assert!(g.matches("*]?", Case::Sensitive));
+        assert!(!g.matches("a]?", Case::Sensitive));
+
+        let g = Glob::new("[abc");
+        assert!(g.matches("[abc", Case::Sensitive));
+        assert!(!g.matches("a", Case::Sensitive));
+
+        assert!(Glob::new("foo\\").matches("foo\\", Case::Sensitive));
+    }
+
+    #[test]
+    fn case() {
+        let g = Glob::new("ä?[a-c][!X]");
+        assert!(g.matches("ä_by", Case::Sensitive));
+        assert!(!g.matches("ä_By", Case::Sensitive));
+        assert!(g.matches("ä_By", Case::AsciiInsensitive));
+        assert!(!g.matches("Ä_By", Case::AsciiInsensitive));
+        assert!(g.matches("Ä_By", Case::Insensitive));
+        // negated classes exclude both cases
+        assert!(g.matches("ä_bx", Case::Sensitive));
+        assert!(!g.matches("ä_bx", Case::AsciiInsensitive));
+        assert!(!g.matches("ä_bx", Case::Insensitive));
+    }
+
+    #[test]
+    fn literal() {
+        assert_eq!(
+            Glob::new("**foo*").as_literal(),
+            Some((true, "foo".to_owned(), true))
+        );
+        assert_eq!(
+            Glob::new(r"f\*o").as_literal(),
+            Some((false, "f*o".to_owned(), false))
+        );
+        assert_eq!(Glob::new("f?o").as_literal(), None);
+        assert_eq!(
+            Glob::new("*").as_literal(),
+            Some((true, String::new(), false))
+        );
+    }
+}
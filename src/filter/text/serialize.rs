@@ -0,0 +1,142 @@
+//! Serializes equality filters as plain strings and all other filters as
+//! externally tagged variants like `{"contains":"foo"}`, with case folding
+//! wrapped around them as `{"ignore_case":{"contains":"foo"}}`.
+//!
+//! Since a filter may be a string or a map, deserializing requires a
+//! self-describing format.
+
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Case, Mode, TextFilter};
+
+const VARIANTS: &[&str] = &[
+    "contains",
+    "starts_with",
+    "ends_with",
+    "glob",
+    "regex",
+    "ignore_case",
+    "ignore_ascii_case",
+];
+
+/// Serializes a filter ignoring its case folding.
+struct CaseSensitive<'a>(&'a TextFilter);
+
+impl Serialize for CaseSensitive<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let search = &*self.0.search;
+        let (index, variant) = match self.0.mode {
+            Mode::Exact => return serializer.serialize_str(search),
+            Mode::Contains => (0, "contains"),
+            Mode::StartsWith => (1, "starts_with"),
+            Mode::EndsWith => (2, "ends_with"),
+            Mode::Glob(_) => (3, "glob"),
+            #[cfg(feature = "regex")]
+            Mode::Regex(_) => (4, "regex"),
+        };
+        serializer.serialize_newtype_variant("TextFilter", index, variant, search)
+    }
+}
+
+impl Serialize for TextFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (index, variant) = match self.case {
+            Case::Sensitive => return CaseSensitive(self).serialize(serializer),
+            Case::Insensitive => (5, "ignore_case"),
+            Case::AsciiInsensitive => (6, "ignore_ascii_case"),
+        };
+        serializer.serialize_newtype_variant("TextFilter", index, variant, &CaseSensitive(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for TextFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TextFilterVisitor)
+    }
+}
+
+struct TextFilterVisitor;
+
+impl<'de> Visitor<'de> for TextFilterVisitor {
+    type Value = TextFilter;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string or a map with a single text filter variant")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(TextFilter::equals(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(TextFilter::equals(v))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let Some(variant) = map.next_key::<String>()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+        let filter = match variant.as_str() {
+            "contains" => TextFilter::contains(map.next_value::<String>()?),
+            "starts_with" => TextFilter::starts_with(map.next_value::<String>()?),
+            "ends_with" => TextFilter::ends_with(map.next_value::<String>()?),
+            "glob" => TextFilter::glob(map.next_value::<String>()?),
+            #[cfg(feature = "regex")]
+            "regex" => TextFilter::regex(map.next_value::<String>()?).map_err(de::Error::custom)?,
+            "ignore_case" => map.next_value::<TextFilter>()?.ignore_case(),
+            "ignore_ascii_case" => map.next_value::<TextFilter>()?.ignore_ascii_case(),
+            _ => return Err(de::Error::unknown_variant(&variant, VARIANTS)),
+        };
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn round_trip(f: &TextFilter, json: &str) {
+        assert_eq!(serde_json::to_string(f).unwrap(), json);
+        let parsed: TextFilter = serde_json::from_str(json).unwrap();
+        assert_eq!(&parsed, f);
+    }
+
+    #[test]
+    fn variants() {
+        round_trip(&TextFilter::equals("foo"), r#""foo""#);
+        round_trip(&TextFilter::contains("foo"), r#"{"contains":"foo"}"#);
+        round_trip(&TextFilter::starts_with("foo"), r#"{"starts_with":"foo"}"#);
+        round_trip(&TextFilter::ends_with("foo"), r#"{"ends_with":"foo"}"#);
+        round_trip(&TextFilter::glob("f?o"), r#"{"glob":"f?o"}"#);
+        round_trip(
+            &TextFilter::equals("Foo").ignore_case(),
+            r#"{"ignore_case":"foo"}"#,
+        );
+        round_trip(
+            &TextFilter::contains("Foo").ignore_ascii_case(),
+            r#"{"ignore_ascii_case":{"contains":"foo"}}"#,
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex() {
+        round_trip(&TextFilter::regex("^f+").unwrap(), r#"{"regex":"^f+"}"#);
+        assert!(serde_json::from_str::<TextFilter>(r#"{"regex":"("}"#).is_err());
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let e = serde_json::from_str::<TextFilter>(r#"{"fuzzy":"foo"}"#).unwrap_err();
+        assert!(e.to_string().starts_with("unknown variant `fuzzy`"));
+        assert!(serde_json::from_str::<TextFilter>(r#"{"glob":"a","contains":"b"}"#).is_err());
+        assert!(serde_json::from_str::<TextFilter>("{}").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+use super::cost;
+use super::{Cost, FalsyDefault, Filter, Infix, Optimizable, Or, TextFilter, TruthyDefault};
+
+mod automaton;
+use automaton::Automaton;
+
+/// A disjunction of [`TextFilter`]s.
+///
+/// Exact filters are looked up in a hash set and substring filters are
+/// matched in a single pass over the input, all other filters are tested
+/// one by one.
+#[derive(Debug, Clone)]
+pub struct TextSet {
+    exact: HashSet<Box<str>>,
+    substrings: Vec<Box<str>>,
+    automaton: Automaton,
+    other: Vec<TextFilter>,
+}
+
+impl TextSet {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            exact: HashSet::new(),
+            substrings: Vec::new(),
+            automaton: Automaton::new(Vec::<&str>::new()),
+            other: Vec::new(),
+        }
+    }
+
+    /// Number of filters in the set.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.substrings.len() + self.other.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The exact strings, substrings and other filters in sorted order, for
+    /// stable output.
+    fn sorted(&self) -> (Vec<&str>, Vec<&str>, Vec<&TextFilter>) {
+        let mut exact = self.exact.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        exact.sort_unstable();
+        let mut substrings = self
+            .substrings
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>();
+        substrings.sort_unstable();
+        let mut other = self.other.iter().collect::<Vec<_>>();
+        other.sort_by_cached_key(ToString::to_string);
+        (exact, substrings, other)
+    }
+}
+
+/// Sets are equal if they contain equal filters, regardless of order.
+impl PartialEq for TextSet {
+    fn eq(&self, other: &Self) -> bool {
+        if self.exact != other.exact || self.len() != other.len() {
+            return false;
+        }
+        let (_, substrings, filters) = self.sorted();
+        let (_, other_substrings, other_filters) = other.sorted();
+        substrings == other_substrings && filters == other_filters
+    }
+}
+
+impl Eq for TextSet {}
+
+impl std::hash::Hash for TextSet {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sorted().hash(state);
+    }
+}
+
+impl Default for TextSet {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filter<str> for TextSet {
+    #[inline]
+    fn matches(&self, obj: &str) -> bool {
+        self.exact.contains(obj)
+            || self.automaton.is_match(obj.as_bytes())
+            || self.other.iter().any(|f| f.matches(obj))
+    }
+}
+
+impl Cost for TextSet {
+    #[inline]
+    fn cost(&self) -> f64 {
+        let lookups = f64::from(u8::from(!self.exact.is_empty()))
+            + f64::from(u8::from(!self.substrings.is_empty())) * 4.0;
+        lookups + cost::sequence(&self.other, true).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        let exact = 1.0 - 0.9f64.powi(self.exact.len().try_into().unwrap_or(i32::MAX));
+        let substrings = 1.0 - 0.5f64.powi(self.substrings.len().try_into().unwrap_or(i32::MAX));
+        let other = cost::sequence(&self.other, true).1;
+        1.0 - (1.0 - exact) * (1.0 - substrings) * (1.0 - other)
+    }
+}
+
+impl std::fmt::Display for TextSet {
+    /// Writes the set as `set("foo", contains("bar"), ...)`, each kind of
+    /// filter in sorted order.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (exact, substrings, other) = self.sorted();
+        f.write_str("set(")?;
+        let mut sep = "";
+        for s in exact {
+            write!(f, "{sep}{s:?}")?;
+            sep = ", ";
+        }
+        for s in substrings {
+            write!(f, "{sep}contains({s:?})")?;
+            sep = ", ";
+        }
+        for other in other {
+            write!(f, "{sep}{other}")?;
+            sep = ", ";
+        }
+        f.write_str(")")
+    }
+}
+
+impl Infix for TextSet {}
+
+/// Serialized as a sequence of its [`TextFilter`]s.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TextSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let (exact, substrings, other) = self.sorted();
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for s in exact {
+            seq.serialize_element(&TextFilter::equals(s))?;
+        }
+        for s in substrings {
+            seq.serialize_element(&TextFilter::contains(s))?;
+        }
+        for other in other {
+            seq.serialize_element(other)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TextSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<TextFilter>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+impl Optimizable for TextSet {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        if self.substrings.iter().any(|s| s.is_empty())
+            || self.other.iter().any(|f| f.as_bool() == Some(true))
+        {
+            return Some(true);
+        }
+        if self.is_empty() {
+            return Some(false);
+        }
+        None
+    }
+}
+
+impl TruthyDefault for TextSet {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self::from_iter([TextFilter::contains("")])
+    }
+}
+
+impl FalsyDefault for TextSet {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<TextFilter> for TextSet {
+    fn extend<I: IntoIterator<Item = TextFilter>>(&mut self, iter: I) {
+        let substrings = self.substrings.len();
+        for f in iter {
+            if let Some(exact) = f.exact() {
+                self.exact.insert(exact.into());
+            } else if let Some(substring) = f.substring() {
+                self.substrings.push(substring.into());
+            } else {
+                self.other.push(f);
+            }
+        }
+        if self.substrings.len() != substrings {
+            self.automaton = Automaton::new(self.substrings.iter().map(|s| s.as_bytes()));
+        }
+    }
+}
+
+impl FromIterator<TextFilter> for TextSet {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = TextFilter>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl From<Or<TextFilter>> for TextSet {
+    #[inline]
+    fn from(mut value: Or<TextFilter>) -> Self {
+        Self::from_iter(std::mem::take(&mut *value))
+    }
+}
+
+impl IntoIterator for TextSet {
+    type Item = TextFilter;
+    type IntoIter = std::vec::IntoIter<TextFilter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let exact = self.exact.into_iter().map(TextFilter::equals);
+        let substrings = self.substrings.into_iter().map(TextFilter::contains);
+        let filters: Vec<_> = exact.chain(substrings).chain(self.other).collect();
+        filters.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact() {
+        let set = TextSet::from_iter(["foo", "bar"].map(TextFilter::from));
+
+        assert!(set.matches("foo"));
+        assert!(set.matches("bar"));
+        assert!(!set.matches("foobar"));
+        assert_eq!(set.as_bool(), None);
+    }
+
+    #[test]
+    fn substrings() {
+        let set = TextSet::from_iter(["oob", "xyz"].map(TextFilter::contains));
+
+        assert!(set.matches("foobar"));
+        assert!(set.matches("_xyz_"));
+        assert!(!set.matches("fooxy"));
+    }
+
+    #[test]
+    fn mixed() {
+        let or = Or::from(vec![
+            TextFilter::from("foo"),
+            TextFilter::contains("bar"),
+            TextFilter::starts_with("baz"),
+        ]);
+        let set = TextSet::from(or);
+
+        assert_eq!(set.len(), 3);
+        assert!(set.matches("foo"));
+        assert!(set.matches("xbarx"));
+        assert!(set.matches("bazx"));
+        assert!(!set.matches("xbaz"));
+
+        let mut set = TextSet::from_iter(set);
+        set.extend([TextFilter::contains("qux")]);
+        assert_eq!(set.len(), 4);
+        assert!(set.matches("xbarx"));
+        assert!(set.matches("xquxx"));
+    }
+
+    #[test]
+    fn eq() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let filters = [
+            TextFilter::glob("a*b"),
+            TextFilter::ends_with("c"),
+            TextFilter::equals("D").ignore_case(),
+            TextFilter::contains("e"),
+        ];
+        let set = TextSet::from_iter(filters.clone());
+        let reversed = TextSet::from_iter(filters.into_iter().rev());
+        assert_eq!(set, reversed);
+        assert_eq!(set.to_string(), reversed.to_string());
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&set), state.hash_one(&reversed));
+
+        let fewer = TextSet::from_iter([TextFilter::glob("a*b"), TextFilter::ends_with("c")]);
+        assert_ne!(set, fewer);
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(TextSet::falsy_default().as_bool(), Some(false));
+        assert_eq!(TextSet::truthy_default().as_bool(), Some(true));
+        assert!(TextSet::truthy_default().matches(""));
+    }
+}
@@ -0,0 +1,129 @@
+/// An Aho-Corasick automaton answering whether any of its patterns occurs in
+/// a text.
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    states: Vec<State>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    /// Transitions sorted by byte.
+    next: Vec<(u8, u32)>,
+    fail: u32,
+    /// Whether a pattern ends here, directly or via a failure link.
+    output: bool,
+}
+
+impl State {
+    #[inline]
+    fn next(&self, byte: u8) -> Option<u32> {
+        self.next
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.next[i].1)
+    }
+}
+
+impl Automaton {
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let mut states = vec![State::default()];
+
+        for pattern in patterns {
+            let mut current = 0;
+            for &byte in pattern.as_ref() {
+                current = match states[current].next(byte) {
+                    Some(next) => next as usize,
+                    None => {
+                        let next = states.len();
+                        states.push(State::default());
+                        let transitions = &mut states[current].next;
+                        let i = transitions.partition_point(|&(b, _)| b < byte);
+                        transitions.insert(i, (byte, next as u32));
+                        next
+                    }
+                };
+            }
+            states[current].output = true;
+        }
+
+        // breadth first, so failure targets are always complete
+        let mut queue = std::collections::VecDeque::from([0u32]);
+        while let Some(current) = queue.pop_front() {
+            for i in 0..states[current as usize].next.len() {
+                let (byte, child) = states[current as usize].next[i];
+                queue.push_back(child);
+                if current == 0 {
+                    continue;
+                }
+                let mut fail = states[current as usize].fail;
+                let fail = loop {
+                    if let Some(next) = states[fail as usize].next(byte) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = states[fail as usize].fail;
+                };
+                states[child as usize].fail = fail;
+                states[child as usize].output |= states[fail as usize].output;
+            }
+        }
+
+        Self { states }
+    }
+
+    /// Whether any pattern occurs in `text`.
+    pub fn is_match(&self, text: &[u8]) -> bool {
+        let states = &self.states;
+        if states[0].output {
+            return true;
+        }
+        let mut current = 0u32;
+        for &byte in text {
+            current = loop {
+                if let Some(next) = states[current as usize].next(byte) {
+                    break next;
+                }
+                if current == 0 {
+                    break 0;
+                }
+                current = states[current as usize].fail;
+            };
+            if states[current as usize].output {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches() {
+        let a = Automaton::new(["he", "she", "his", "hers"]);
+
+        assert!(a.is_match(b"ushers"));
+        assert!(a.is_match(b"ahis"));
+        assert!(a.is_match(b"xxhe"));
+        assert!(!a.is_match(b"hi"));
+        assert!(!a.is_match(b""));
+    }
+
+    #[test]
+    fn failure_links() {
+        let a = Automaton::new(["abcd", "bce"]);
+
+        assert!(a.is_match(b"abce"));
+        assert!(!a.is_match(b"abcbd"));
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Automaton::new([""]).is_match(b""));
+        assert!(!Automaton::new(Vec::<&str>::new()).is_match(b"foo"));
+    }
+}
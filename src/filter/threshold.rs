@@ -0,0 +1,437 @@
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if at least `n` filters match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AtLeast<F> {
+    pub n: usize,
+    pub filters: Vec<F>,
+}
+
+/// Matches if at most `n` filters match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AtMost<F> {
+    pub n: usize,
+    pub filters: Vec<F>,
+}
+
+/// Matches if exactly `n` filters match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Exactly<F> {
+    pub n: usize,
+    pub filters: Vec<F>,
+}
+
+impl<F> AtLeast<F> {
+    #[inline]
+    #[must_use]
+    pub fn new(n: usize, filters: impl Into<Vec<F>>) -> Self {
+        Self {
+            n,
+            filters: filters.into(),
+        }
+    }
+}
+
+impl<F> AtMost<F> {
+    #[inline]
+    #[must_use]
+    pub fn new(n: usize, filters: impl Into<Vec<F>>) -> Self {
+        Self {
+            n,
+            filters: filters.into(),
+        }
+    }
+}
+
+impl<F> Exactly<F> {
+    #[inline]
+    #[must_use]
+    pub fn new(n: usize, filters: impl Into<Vec<F>>) -> Self {
+        Self {
+            n,
+            filters: filters.into(),
+        }
+    }
+}
+
+/// Whether the number of matching filters is within `min..=max`, stopping as
+/// soon as the outcome is certain.
+#[inline]
+fn matches_between<F: Filter<T>, T: ?Sized>(
+    filters: &[F],
+    obj: &T,
+    min: usize,
+    max: usize,
+) -> bool {
+    let mut hits = 0;
+    for (i, f) in filters.iter().enumerate() {
+        if f.matches(obj) {
+            hits += 1;
+        }
+        let remaining = filters.len() - i - 1;
+        if hits > max || hits + remaining < min {
+            return false;
+        }
+        if hits >= min && hits + remaining <= max {
+            return true;
+        }
+    }
+    (min..=max).contains(&hits)
+}
+
+/// Whether the number of unconditionally matching filters is within
+/// `min..=max`, if that's already certain.
+#[inline]
+fn as_bool_between<F: Optimizable>(filters: &[F], min: usize, max: usize) -> Option<bool> {
+    // bounds of the possible number of matching filters
+    let (mut lo, mut hi) = (0, 0);
+    for f in filters {
+        match f.as_bool() {
+            Some(true) => {
+                lo += 1;
+                hi += 1;
+            }
+            None => hi += 1,
+            Some(false) => {}
+        }
+    }
+    if min <= lo && hi <= max {
+        Some(true)
+    } else if hi < min || lo > max {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Optimizes and removes unconditional filters, returning how many of them
+/// matched.
+#[inline]
+fn remove_constants<F: Optimizable>(filters: &mut Vec<F>) -> usize {
+    let mut hits = 0;
+    filters.retain_mut(|f| {
+        f.optimize();
+        match f.as_bool() {
+            Some(b) => {
+                hits += usize::from(b);
+                false
+            }
+            None => true,
+        }
+    });
+    hits
+}
+
+/// The expected cost and selectivity of [`matches_between`], if the filters
+/// are independent.
+///
+/// Like [`cost::sequence`](super::cost::sequence), but evaluation stops once
+/// the number of hits is certain to be in or out of `min..=max`.
+fn cost_between<F: Cost>(filters: &[F], min: usize, max: usize) -> (f64, f64) {
+    // the chance of each number of hits while the outcome is still open
+    let mut open = vec![1.0];
+    let mut cost = 0.0;
+    let mut selectivity = 0.0;
+    for (i, f) in filters.iter().enumerate() {
+        cost += open.iter().sum::<f64>() * f.cost();
+        let p = f.selectivity().clamp(0.0, 1.0);
+        let mut next = vec![0.0; open.len() + 1];
+        for (hits, chance) in open.iter().enumerate() {
+            next[hits] += chance * (1.0 - p);
+            next[hits + 1] += chance * p;
+        }
+        let remaining = filters.len() - i - 1;
+        for (hits, chance) in next.iter_mut().enumerate() {
+            if hits > max || hits + remaining < min {
+                *chance = 0.0;
+            } else if hits >= min && hits + remaining <= max {
+                selectivity += *chance;
+                *chance = 0.0;
+            }
+        }
+        open = next;
+    }
+    if filters.is_empty() && (min..=max).contains(&0) {
+        selectivity = 1.0;
+    }
+    (cost, selectivity)
+}
+
+impl<F: Filter<T>, T: ?Sized> Filter<T> for AtLeast<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        matches_between(&self.filters, obj, self.n, usize::MAX)
+    }
+}
+
+impl<F: Filter<T>, T: ?Sized> Filter<T> for AtMost<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        matches_between(&self.filters, obj, 0, self.n)
+    }
+}
+
+impl<F: Filter<T>, T: ?Sized> Filter<T> for Exactly<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        matches_between(&self.filters, obj, self.n, self.n)
+    }
+}
+
+impl<F: Optimizable> Optimizable for AtLeast<F> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        as_bool_between(&self.filters, self.n, usize::MAX)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        let hits = remove_constants(&mut self.filters);
+        self.n = self.n.saturating_sub(hits);
+
+        match self.as_bool() {
+            Some(true) => *self = Self::truthy_default(),
+            Some(false) => *self = Self::falsy_default(),
+            None => {}
+        }
+    }
+}
+
+impl<F: Optimizable + TruthyDefault> Optimizable for AtMost<F> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        as_bool_between(&self.filters, 0, self.n)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        let hits = remove_constants(&mut self.filters);
+        match self.n.checked_sub(hits) {
+            Some(n) => self.n = n,
+            None => {
+                *self = Self::falsy_default();
+                return;
+            }
+        }
+
+        if self.as_bool() == Some(true) {
+            *self = Self::truthy_default();
+        }
+    }
+}
+
+impl<F: Optimizable> Optimizable for Exactly<F> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        as_bool_between(&self.filters, self.n, self.n)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        let hits = remove_constants(&mut self.filters);
+        match self.n.checked_sub(hits) {
+            Some(n) => self.n = n,
+            None => {
+                *self = Self::falsy_default();
+                return;
+            }
+        }
+
+        match self.as_bool() {
+            Some(true) => *self = Self::truthy_default(),
+            Some(false) => *self = Self::falsy_default(),
+            None => {}
+        }
+    }
+}
+
+impl<F: Cost> Cost for AtLeast<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost_between(&self.filters, self.n, usize::MAX).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost_between(&self.filters, self.n, usize::MAX).1
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        for f in &mut self.filters {
+            f.reorder();
+        }
+    }
+}
+
+impl<F: Cost> Cost for AtMost<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost_between(&self.filters, 0, self.n).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost_between(&self.filters, 0, self.n).1
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        for f in &mut self.filters {
+            f.reorder();
+        }
+    }
+}
+
+impl<F: Cost> Cost for Exactly<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        cost_between(&self.filters, self.n, self.n).0
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        cost_between(&self.filters, self.n, self.n).1
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        for f in &mut self.filters {
+            f.reorder();
+        }
+    }
+}
+
+impl<F> TruthyDefault for AtLeast<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self::new(0, Vec::new())
+    }
+}
+
+impl<F> FalsyDefault for AtLeast<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self::new(1, Vec::new())
+    }
+}
+
+impl<F> TruthyDefault for AtMost<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self::new(0, Vec::new())
+    }
+}
+
+impl<F: TruthyDefault> FalsyDefault for AtMost<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self::new(0, vec![F::truthy_default()])
+    }
+}
+
+impl<F> TruthyDefault for Exactly<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self::new(0, Vec::new())
+    }
+}
+
+impl<F> FalsyDefault for Exactly<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self::new(1, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{And, Or, Range, TestFilter};
+    use super::*;
+
+    fn ranges() -> Vec<Range<i32>> {
+        vec![Range::lt(5), Range::gt(0), Range::between(2, 3)]
+    }
+
+    #[test]
+    fn at_least() {
+        let f = AtLeast::new(2, ranges());
+        assert!(f.matches(&1));
+        assert!(f.matches(&3));
+        assert!(!f.matches(&7));
+
+        assert_eq!(AtLeast::new(0, ranges()).as_bool(), Some(true));
+        assert_eq!(AtLeast::new(4, ranges()).as_bool(), Some(false));
+        assert_eq!(AtLeast::new(2, ranges()).as_bool(), None);
+    }
+
+    #[test]
+    fn at_most() {
+        let f = AtMost::new(1, ranges());
+        assert!(f.matches(&7));
+        assert!(f.matches(&-1));
+        assert!(!f.matches(&1));
+
+        assert_eq!(AtMost::new(3, ranges()).as_bool(), Some(true));
+        assert_eq!(AtMost::new(1, ranges()).as_bool(), None);
+    }
+
+    #[test]
+    fn exactly() {
+        let f = Exactly::new(1, ranges());
+        assert!(f.matches(&-1));
+        assert!(f.matches(&7));
+        assert!(!f.matches(&1));
+        assert!(!f.matches(&3));
+
+        assert_eq!(Exactly::new(4, ranges()).as_bool(), Some(false));
+    }
+
+    #[test]
+    fn short_circuit() {
+        let filters = || vec![TestFilter::Any, TestFilter::Any, TestFilter::Panic];
+
+        assert!(AtLeast::new(2, filters()).matches(&()));
+        assert!(!AtMost::new(1, filters()).matches(&()));
+        assert!(!Exactly::new(1, filters()).matches(&()));
+    }
+
+    #[test]
+    fn optimize() {
+        let filters = || vec![TestFilter::Any, TestFilter::None, TestFilter::Any];
+
+        let mut f = AtLeast::new(2, filters());
+        f.optimize();
+        assert_eq!((f.n, f.filters.len()), (0, 0));
+        assert_eq!(f.as_bool(), Some(true));
+
+        let mut f = AtMost::new(1, filters());
+        f.optimize();
+        assert_eq!(f.as_bool(), Some(false));
+
+        let mut f = Exactly::new(2, filters());
+        f.optimize();
+        assert_eq!(f.as_bool(), Some(true));
+
+        let mut f = Exactly::new(1, filters());
+        f.optimize();
+        assert_eq!(f.as_bool(), Some(false));
+    }
+
+    #[test]
+    fn cost() {
+        let filters = || vec![TestFilter::Any, TestFilter::Panic, TestFilter::None];
+        // one hit or all hits decide like `Or` and `And`
+        let or = Or::from(filters());
+        assert_eq!(AtLeast::new(1, filters()).cost(), or.cost());
+        assert_eq!(AtLeast::new(1, filters()).selectivity(), or.selectivity());
+        let and = And::from(filters());
+        assert_eq!(Exactly::new(3, filters()).cost(), and.cost());
+        assert_eq!(Exactly::new(3, filters()).selectivity(), and.selectivity());
+
+        // the last filter only runs if the second one didn't match
+        assert_eq!(AtMost::new(1, filters()).cost(), 2.5);
+        assert_eq!(AtMost::new(1, filters()).selectivity(), 0.5);
+        assert_eq!(AtMost::new(0, Vec::<TestFilter>::new()).selectivity(), 1.0);
+    }
+}
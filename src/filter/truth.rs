@@ -0,0 +1,152 @@
+/// The result of evaluating a filter on partially known data.
+///
+/// Combining values follows Kleene's three-valued logic, so [`Truth::Unknown`]
+/// only propagates if the other operand doesn't decide the outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truth {
+    False,
+    Unknown,
+    True,
+}
+
+impl Truth {
+    /// Returns the outcome, if it's known.
+    #[inline]
+    #[must_use]
+    pub const fn known(self) -> Option<bool> {
+        match self {
+            Truth::False => Some(false),
+            Truth::Unknown => None,
+            Truth::True => Some(true),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_true(self) -> bool {
+        matches!(self, Truth::True)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_false(self) -> bool {
+        matches!(self, Truth::False)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_unknown(self) -> bool {
+        matches!(self, Truth::Unknown)
+    }
+}
+
+impl From<bool> for Truth {
+    #[inline]
+    fn from(value: bool) -> Self {
+        if value {
+            Truth::True
+        } else {
+            Truth::False
+        }
+    }
+}
+
+impl From<Option<bool>> for Truth {
+    #[inline]
+    fn from(value: Option<bool>) -> Self {
+        value.map_or(Truth::Unknown, Truth::from)
+    }
+}
+
+impl std::ops::Not for Truth {
+    type Output = Self;
+    #[inline]
+    fn not(self) -> Self {
+        match self {
+            Truth::False => Truth::True,
+            Truth::Unknown => Truth::Unknown,
+            Truth::True => Truth::False,
+        }
+    }
+}
+
+impl std::ops::BitAnd for Truth {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Truth::False, _) | (_, Truth::False) => Truth::False,
+            (Truth::True, Truth::True) => Truth::True,
+            _ => Truth::Unknown,
+        }
+    }
+}
+
+impl std::ops::BitOr for Truth {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Truth::True, _) | (_, Truth::True) => Truth::True,
+            (Truth::False, Truth::False) => Truth::False,
+            _ => Truth::Unknown,
+        }
+    }
+}
+
+/// A filter that can tell apart objects not matching from objects lacking
+/// the information to decide.
+pub trait TriFilter<T: ?Sized> {
+    fn eval(&self, obj: &T) -> Truth;
+}
+
+impl<T: ?Sized> TriFilter<T> for bool {
+    #[inline]
+    fn eval(&self, _: &T) -> Truth {
+        Truth::from(*self)
+    }
+}
+
+impl<F: TriFilter<T> + ?Sized, T: ?Sized> TriFilter<T> for Box<F> {
+    #[inline]
+    fn eval(&self, obj: &T) -> Truth {
+        self.as_ref().eval(obj)
+    }
+}
+
+impl<F: TriFilter<T> + ?Sized, T: ?Sized> TriFilter<T> for &F {
+    #[inline]
+    fn eval(&self, obj: &T) -> Truth {
+        (**self).eval(obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Truth; 3] = [Truth::False, Truth::Unknown, Truth::True];
+
+    #[test]
+    fn kleene() {
+        for a in ALL {
+            assert_eq!(a & Truth::False, Truth::False);
+            assert_eq!(a | Truth::True, Truth::True);
+            assert_eq!(a & Truth::True, a);
+            assert_eq!(a | Truth::False, a);
+            assert_eq!(!!a, a);
+        }
+        assert_eq!(Truth::Unknown & Truth::Unknown, Truth::Unknown);
+        assert_eq!(Truth::Unknown | Truth::Unknown, Truth::Unknown);
+        assert_eq!(!Truth::Unknown, Truth::Unknown);
+    }
+
+    #[test]
+    fn conversions() {
+        for b in [false, true] {
+            assert_eq!(Truth::from(b).known(), Some(b));
+            assert_eq!(Truth::from(Some(b)).known(), Some(b));
+        }
+        assert_eq!(Truth::from(None), Truth::Unknown);
+    }
+}
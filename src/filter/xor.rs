@@ -0,0 +1,146 @@
+use super::{Cost, FalsyDefault, Filter, Optimizable, TruthyDefault};
+
+/// Matches if an odd number of filters match.
+///
+/// For "exactly one of" use [`Exactly`](super::Exactly).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xor<F>(Vec<F>);
+
+impl<F> From<Vec<F>> for Xor<F> {
+    #[inline]
+    fn from(value: Vec<F>) -> Self {
+        Self(value)
+    }
+}
+
+impl<F> std::ops::Deref for Xor<F> {
+    type Target = Vec<F>;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F> std::ops::DerefMut for Xor<F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<F: Filter<T>, T: ?Sized> Filter<T> for Xor<F> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.0.iter().filter(|f| f.matches(obj)).count() % 2 == 1
+    }
+}
+
+impl<F: Optimizable + TruthyDefault> Optimizable for Xor<F> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        let mut odd = false;
+        for f in &self.0 {
+            odd ^= f.as_bool()?;
+        }
+        Some(odd)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        let mut flip = false;
+        self.0.retain_mut(|f| {
+            f.optimize();
+            match f.as_bool() {
+                // unconditional filters only affect the parity
+                Some(b) => {
+                    flip ^= b;
+                    false
+                }
+                None => true,
+            }
+        });
+
+        if flip {
+            self.0.push(F::truthy_default());
+        }
+    }
+}
+
+/// All filters are evaluated, so their costs add up.
+impl<F: Cost> Cost for Xor<F> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.0.iter().map(Cost::cost).sum()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        // the chance of an odd number of matches, for independent filters
+        let even_minus_odd: f64 = self
+            .0
+            .iter()
+            .map(|f| 1.0 - 2.0 * f.selectivity().clamp(0.0, 1.0))
+            .product();
+        (1.0 - even_minus_odd) / 2.0
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        for f in &mut self.0 {
+            f.reorder();
+        }
+    }
+}
+
+impl<F: TruthyDefault> TruthyDefault for Xor<F> {
+    #[inline]
+    fn truthy_default() -> Self {
+        Self(vec![F::truthy_default()])
+    }
+}
+
+impl<F> FalsyDefault for Xor<F> {
+    #[inline]
+    fn falsy_default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TestFilter;
+    use super::*;
+
+    #[test]
+    fn parity() {
+        let f = Xor::from(vec![TestFilter::Any, TestFilter::None, TestFilter::Any]);
+        assert!(!f.matches(&()));
+        assert_eq!(f.as_bool(), Some(false));
+
+        let f = Xor::from(vec![TestFilter::Any, TestFilter::None]);
+        assert!(f.matches(&()));
+        assert_eq!(f.as_bool(), Some(true));
+    }
+
+    #[test]
+    fn optimize() {
+        let mut f = Xor::from(vec![TestFilter::Any, TestFilter::Any, TestFilter::Any]);
+        f.optimize();
+        assert_eq!(f.len(), 1);
+        assert_eq!(f.as_bool(), Some(true));
+
+        let mut f = Xor::from(vec![TestFilter::Any, TestFilter::None, TestFilter::Any]);
+        f.optimize();
+        assert_eq!(f.len(), 0);
+        assert_eq!(f.as_bool(), Some(false));
+    }
+
+    #[test]
+    fn cost() {
+        let f = Xor::from(vec![TestFilter::Any, TestFilter::Panic, TestFilter::None]);
+        assert_eq!(f.cost(), 3.0);
+        assert_eq!(f.selectivity(), 0.5);
+        let f = Xor::from(vec![TestFilter::Any, TestFilter::Any, TestFilter::Any]);
+        assert_eq!(f.selectivity(), 1.0);
+    }
+}
@@ -0,0 +1,70 @@
+use super::{Cost, Filter, Optimizable};
+
+/// Matches if exactly one of the two filters matches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XorWith<A, B>(pub A, pub B);
+
+impl<A: Filter<T>, B: Filter<T>, T: ?Sized> Filter<T> for XorWith<A, B> {
+    #[inline]
+    fn matches(&self, obj: &T) -> bool {
+        self.0.matches(obj) != self.1.matches(obj)
+    }
+}
+
+impl<A: Optimizable, B: Optimizable> Optimizable for XorWith<A, B> {
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Some(self.0.as_bool()? != self.1.as_bool()?)
+    }
+
+    #[inline]
+    fn optimize(&mut self) {
+        self.0.optimize();
+        self.1.optimize();
+    }
+}
+
+/// Both filters are evaluated, so their costs add up.
+impl<A: Cost, B: Cost> Cost for XorWith<A, B> {
+    #[inline]
+    fn cost(&self) -> f64 {
+        self.0.cost() + self.1.cost()
+    }
+
+    #[inline]
+    fn selectivity(&self) -> f64 {
+        let (a, b) = (self.0.selectivity(), self.1.selectivity());
+        a * (1.0 - b) + (1.0 - a) * b
+    }
+
+    #[inline]
+    fn reorder(&mut self) {
+        self.0.reorder();
+        self.1.reorder();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TestFilter;
+    use super::*;
+
+    #[test]
+    fn constant() {
+        assert_eq!(
+            XorWith(TestFilter::Any, TestFilter::Any).as_bool(),
+            Some(false)
+        );
+        assert_eq!(
+            XorWith(TestFilter::Any, TestFilter::None).as_bool(),
+            Some(true)
+        );
+        assert_eq!(XorWith(TestFilter::Panic, TestFilter::Any).as_bool(), None);
+    }
+
+    #[test]
+    fn matches() {
+        assert!(XorWith(TestFilter::Any, TestFilter::None).matches(&()));
+        assert!(!XorWith(TestFilter::None, TestFilter::None).matches(&()));
+    }
+}
@@ -3,7 +3,13 @@ pub use filter::*;
 
 pub mod prelude {
     pub use super::And;
+    pub use super::FilterExt;
+    pub use super::IteratorFilterExt;
     pub use super::Not;
     pub use super::Or;
+    pub use super::Range;
     pub use super::TextFilter as Text;
+    pub use super::TextSet;
+    #[cfg(feature = "datalink")]
+    pub use super::{DataFilter, LinkFilter};
 }